@@ -0,0 +1,209 @@
+use std::fmt;
+
+use crate::pgoutput::DecodeError;
+
+/// A PostgreSQL SQLSTATE error class, parsed from an `ErrorResponse`'s `code`
+/// field. Only the class (first two characters) is distinguished; the full
+/// five-character code is kept in `ReplicationError::Server` for logging.
+///
+/// See <https://www.postgresql.org/docs/current/errcodes-appendix.html>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlState {
+    /// Class 08: connection exceptions (e.g. `08006` connection failure).
+    ConnectionException,
+    /// Class 57: operator intervention (e.g. `57P01` admin shutdown,
+    /// `57P03` cannot connect now) — the server is restarting or shedding
+    /// load, not permanently refusing the request.
+    OperatorIntervention,
+    /// Class 42: syntax or access-rule violation (e.g. a missing replication
+    /// slot or publication) — retrying won't fix this.
+    SyntaxOrAccessRuleViolation,
+    /// Any other class, kept verbatim for logging.
+    Other(String),
+}
+
+impl SqlState {
+    /// Scan for a five-character SQLSTATE code (one digit followed by four
+    /// alphanumerics) immediately following a `SQLSTATE` marker in a server
+    /// error message, e.g. `"...(SQLSTATE 42704)"`. The `pgwire_replication`
+    /// client surfaces the server's `ErrorResponse` as a formatted string
+    /// rather than a structured type, so this is a heuristic rather than a
+    /// field lookup — anchored to the `SQLSTATE` marker rather than any
+    /// standalone 5-character token, since a bare scan would also match
+    /// incidental numbers in connection-level text (ports, PIDs, timeouts).
+    pub fn parse_from_message(message: &str) -> Option<Self> {
+        let lower = message.to_ascii_lowercase();
+        let marker = lower.find("sqlstate")?;
+        let after_marker = &message[marker + "sqlstate".len()..];
+
+        let code = after_marker
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .find(|tok| !tok.is_empty())
+            .filter(|tok| {
+                tok.len() == 5
+                    && tok.as_bytes()[0].is_ascii_digit()
+                    && tok.chars().all(|c| c.is_ascii_alphanumeric())
+            })?;
+
+        Some(match &code[..2] {
+            "08" => SqlState::ConnectionException,
+            "57" => SqlState::OperatorIntervention,
+            "42" => SqlState::SyntaxOrAccessRuleViolation,
+            _ => SqlState::Other(code.to_string()),
+        })
+    }
+
+    /// Whether an error of this class is worth retrying after a backoff, as
+    /// opposed to one that will keep failing the same way (e.g. a dropped
+    /// replication slot). An unrecognized class (`Other`) defaults to
+    /// retriable, same as no SQLSTATE being found at all — only classes
+    /// known to be permanent (e.g. a missing slot/publication) give up.
+    pub fn is_retriable(&self) -> bool {
+        !matches!(self, SqlState::SyntaxOrAccessRuleViolation)
+    }
+}
+
+impl fmt::Display for SqlState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlState::ConnectionException => write!(f, "08"),
+            SqlState::OperatorIntervention => write!(f, "57"),
+            SqlState::SyntaxOrAccessRuleViolation => write!(f, "42"),
+            SqlState::Other(code) => write!(f, "{}", code),
+        }
+    }
+}
+
+/// An error from the replication connection or decode path, classified so
+/// `ExponentialBackoff`-driven reconnect logic can decide whether to retry
+/// rather than blindly retrying every failure.
+#[derive(Debug)]
+pub enum ReplicationError {
+    /// A message from pgoutput couldn't be decoded.
+    Decode(DecodeError),
+    /// The server reported an `ErrorResponse`, classified by SQLSTATE.
+    Server { code: SqlState, message: String },
+    /// The connection or client raised an error with no identifiable
+    /// SQLSTATE (e.g. a TCP-level failure reported as plain text).
+    Other(String),
+}
+
+impl ReplicationError {
+    /// Classify an error message from the replication client. Looks for a
+    /// SQLSTATE code first; falls back to `Other` if none is found.
+    pub fn from_message(message: impl Into<String>) -> Self {
+        let message = message.into();
+        match SqlState::parse_from_message(&message) {
+            Some(code) => ReplicationError::Server { code, message },
+            None => ReplicationError::Other(message),
+        }
+    }
+
+    /// Whether the `ExponentialBackoff` loop should retry after this error,
+    /// rather than giving up.
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            ReplicationError::Decode(_) => true,
+            ReplicationError::Server { code, .. } => code.is_retriable(),
+            ReplicationError::Other(_) => true,
+        }
+    }
+}
+
+impl fmt::Display for ReplicationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplicationError::Decode(e) => write!(f, "{}", e),
+            ReplicationError::Server { code, message } => {
+                write!(f, "server error [{}]: {}", code, message)
+            }
+            ReplicationError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ReplicationError {}
+
+impl From<DecodeError> for ReplicationError {
+    fn from(e: DecodeError) -> Self {
+        ReplicationError::Decode(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_classes_anchored_to_marker() {
+        assert_eq!(
+            SqlState::parse_from_message("FATAL: terminating (SQLSTATE 08006)"),
+            Some(SqlState::ConnectionException)
+        );
+        assert_eq!(
+            SqlState::parse_from_message("server shutting down, SQLSTATE 57P01"),
+            Some(SqlState::OperatorIntervention)
+        );
+        assert_eq!(
+            SqlState::parse_from_message("relation does not exist (SQLSTATE 42704)"),
+            Some(SqlState::SyntaxOrAccessRuleViolation)
+        );
+    }
+
+    #[test]
+    fn parses_unrecognized_class_as_other() {
+        assert_eq!(
+            SqlState::parse_from_message("SQLSTATE 99999"),
+            Some(SqlState::Other("99999".to_string()))
+        );
+    }
+
+    #[test]
+    fn marker_match_is_case_insensitive() {
+        assert_eq!(
+            SqlState::parse_from_message("sqlstate 08006"),
+            Some(SqlState::ConnectionException)
+        );
+    }
+
+    #[test]
+    fn no_marker_means_no_match_even_with_a_five_char_numeric_token() {
+        // Regression: a bare scan would mistake an incidental 5-digit number
+        // (port, PID, timeout) for a SQLSTATE code. Without the "SQLSTATE"
+        // marker, nothing should match.
+        assert_eq!(
+            SqlState::parse_from_message("could not connect to server: port 54321 timed out"),
+            None
+        );
+    }
+
+    #[test]
+    fn retriability_matches_class() {
+        assert!(!SqlState::SyntaxOrAccessRuleViolation.is_retriable());
+        assert!(SqlState::ConnectionException.is_retriable());
+        assert!(SqlState::OperatorIntervention.is_retriable());
+        assert!(SqlState::Other("99999".to_string()).is_retriable());
+    }
+
+    #[test]
+    fn from_message_falls_back_to_other_without_a_sqlstate_marker() {
+        let err = ReplicationError::from_message("connection reset by peer");
+        assert!(matches!(err, ReplicationError::Other(_)));
+        assert!(err.is_retriable());
+    }
+
+    #[test]
+    fn from_message_classifies_server_errors_and_respects_retriability() {
+        let err = ReplicationError::from_message(
+            "ERROR: publication \"p\" does not exist (SQLSTATE 42704)",
+        );
+        assert!(matches!(
+            err,
+            ReplicationError::Server {
+                code: SqlState::SyntaxOrAccessRuleViolation,
+                ..
+            }
+        ));
+        assert!(!err.is_retriable());
+    }
+}
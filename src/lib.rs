@@ -1,16 +1,31 @@
 #![allow(non_local_definitions)] // False positive with PyO3 macros in Rust 1.93+
 
+#[cfg(feature = "python")]
 use pyo3::exceptions::PyRuntimeError;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 
+mod error;
+mod metrics;
 mod pgoutput;
+#[cfg(feature = "python")]
 mod replication;
 mod utils;
 
-use pgoutput::{message_to_debezium_json, ReplicationMessage};
+#[cfg(feature = "python")]
+use pgoutput::{
+    message_from_cbor, message_to_cbor, message_to_debezium_json, read_ndjson, write_ndjson,
+    ReplicationMessage,
+};
+#[cfg(feature = "python")]
 use replication::LogicalReplicationReader;
 
-/// PostgreSQL logical replication library with pgoutput decoder
+/// PostgreSQL logical replication library with pgoutput decoder.
+///
+/// Building with `--no-default-features` (disabling the default-on `python`
+/// feature) drops this module and all PyO3 bindings, leaving a pure-Rust
+/// `pgoutput` decoder usable as an ordinary Rust dependency.
+#[cfg(feature = "python")]
 #[pymodule]
 fn _pgoutput_decoder(py: Python, m: &PyModule) -> PyResult<()> {
     // Initialize async runtime for pyo3-asyncio
@@ -23,6 +38,10 @@ fn _pgoutput_decoder(py: Python, m: &PyModule) -> PyResult<()> {
 
     // Register functions
     m.add_function(wrap_pyfunction!(message_to_debezium_json, m)?)?;
+    m.add_function(wrap_pyfunction!(write_ndjson, m)?)?;
+    m.add_function(wrap_pyfunction!(read_ndjson, m)?)?;
+    m.add_function(wrap_pyfunction!(message_to_cbor, m)?)?;
+    m.add_function(wrap_pyfunction!(message_from_cbor, m)?)?;
 
     // Register exceptions
     m.add("ReplicationError", py.get_type::<PyRuntimeError>())?;
@@ -0,0 +1,146 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::PyDict;
+
+/// Replication health and throughput counters for a single
+/// [`LogicalReplicationReader`](crate::replication::LogicalReplicationReader).
+///
+/// All fields are atomics so the reader's background task can update them
+/// without taking the `ReaderState` lock. Snapshot them via `stats()` (a
+/// Python dict) or `metrics_text()` (Prometheus text exposition format).
+#[derive(Default)]
+pub struct Metrics {
+    messages_total: AtomicU64,
+    inserts_total: AtomicU64,
+    updates_total: AtomicU64,
+    deletes_total: AtomicU64,
+    decode_failures_total: AtomicU64,
+    bytes_processed_total: AtomicU64,
+    reconnects_total: AtomicU64,
+    current_lsn: AtomicU64,
+    last_ack_lsn: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_message(&self, op: &str, bytes: usize) {
+        self.messages_total.fetch_add(1, Ordering::Relaxed);
+        self.bytes_processed_total
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+        let counter = match op {
+            "c" => &self.inserts_total,
+            "u" => &self.updates_total,
+            "d" => &self.deletes_total,
+            _ => return,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_decode_failure(&self) {
+        self.decode_failures_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_reconnect(&self) {
+        self.reconnects_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn set_current_lsn(&self, lsn: u64) {
+        self.current_lsn.store(lsn, Ordering::Relaxed);
+    }
+
+    pub fn set_last_ack_lsn(&self, lsn: u64) {
+        self.last_ack_lsn.store(lsn, Ordering::Relaxed);
+    }
+
+    pub fn last_ack_lsn(&self) -> u64 {
+        self.last_ack_lsn.load(Ordering::Relaxed)
+    }
+
+    /// Bytes of WAL the server has sent but the reader hasn't yet acknowledged.
+    fn lag_bytes(&self) -> u64 {
+        self.current_lsn
+            .load(Ordering::Relaxed)
+            .saturating_sub(self.last_ack_lsn.load(Ordering::Relaxed))
+    }
+
+    /// Snapshot the counters as a Python dict, suitable for `reader.stats()`.
+    #[cfg(feature = "python")]
+    pub fn to_py_dict(&self, py: Python) -> PyResult<PyObject> {
+        let dict = PyDict::new(py);
+        dict.set_item(
+            "messages_total",
+            self.messages_total.load(Ordering::Relaxed),
+        )?;
+        dict.set_item("inserts_total", self.inserts_total.load(Ordering::Relaxed))?;
+        dict.set_item("updates_total", self.updates_total.load(Ordering::Relaxed))?;
+        dict.set_item("deletes_total", self.deletes_total.load(Ordering::Relaxed))?;
+        dict.set_item(
+            "decode_failures_total",
+            self.decode_failures_total.load(Ordering::Relaxed),
+        )?;
+        dict.set_item(
+            "bytes_processed_total",
+            self.bytes_processed_total.load(Ordering::Relaxed),
+        )?;
+        dict.set_item(
+            "reconnects_total",
+            self.reconnects_total.load(Ordering::Relaxed),
+        )?;
+        dict.set_item("current_lsn", self.current_lsn.load(Ordering::Relaxed))?;
+        dict.set_item("last_ack_lsn", self.last_ack_lsn.load(Ordering::Relaxed))?;
+        dict.set_item("replication_lag_bytes", self.lag_bytes())?;
+        Ok(dict.into())
+    }
+
+    /// Render the counters as Prometheus text exposition format.
+    pub fn to_prometheus_text(&self) -> String {
+        format!(
+            "# HELP pgoutput_messages_total Replication messages converted to change events.\n\
+             # TYPE pgoutput_messages_total counter\n\
+             pgoutput_messages_total {}\n\
+             # HELP pgoutput_inserts_total Insert change events emitted.\n\
+             # TYPE pgoutput_inserts_total counter\n\
+             pgoutput_inserts_total {}\n\
+             # HELP pgoutput_updates_total Update change events emitted.\n\
+             # TYPE pgoutput_updates_total counter\n\
+             pgoutput_updates_total {}\n\
+             # HELP pgoutput_deletes_total Delete change events emitted.\n\
+             # TYPE pgoutput_deletes_total counter\n\
+             pgoutput_deletes_total {}\n\
+             # HELP pgoutput_decode_failures_total pgoutput messages that failed to decode.\n\
+             # TYPE pgoutput_decode_failures_total counter\n\
+             pgoutput_decode_failures_total {}\n\
+             # HELP pgoutput_bytes_processed_total Bytes of WAL data decoded.\n\
+             # TYPE pgoutput_bytes_processed_total counter\n\
+             pgoutput_bytes_processed_total {}\n\
+             # HELP pgoutput_reconnects_total Replication connection re-establishments.\n\
+             # TYPE pgoutput_reconnects_total counter\n\
+             pgoutput_reconnects_total {}\n\
+             # HELP pgoutput_current_lsn Most recent WAL position decoded.\n\
+             # TYPE pgoutput_current_lsn gauge\n\
+             pgoutput_current_lsn {}\n\
+             # HELP pgoutput_last_ack_lsn Most recent WAL position acknowledged to the server.\n\
+             # TYPE pgoutput_last_ack_lsn gauge\n\
+             pgoutput_last_ack_lsn {}\n\
+             # HELP pgoutput_replication_lag_bytes Bytes decoded but not yet acknowledged.\n\
+             # TYPE pgoutput_replication_lag_bytes gauge\n\
+             pgoutput_replication_lag_bytes {}\n",
+            self.messages_total.load(Ordering::Relaxed),
+            self.inserts_total.load(Ordering::Relaxed),
+            self.updates_total.load(Ordering::Relaxed),
+            self.deletes_total.load(Ordering::Relaxed),
+            self.decode_failures_total.load(Ordering::Relaxed),
+            self.bytes_processed_total.load(Ordering::Relaxed),
+            self.reconnects_total.load(Ordering::Relaxed),
+            self.current_lsn.load(Ordering::Relaxed),
+            self.last_ack_lsn.load(Ordering::Relaxed),
+            self.lag_bytes(),
+        )
+    }
+}
@@ -0,0 +1,36 @@
+//! CBOR encoding for `ReplicationMessage`, gated behind the `python` feature
+//! since `ReplicationMessage`'s fields are `PyObject`s.
+#![cfg(feature = "python")]
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use super::messages::{replication_message_to_json_value, ReplicationMessage, StringDecodePolicy};
+use super::ndjson::replication_message_from_json;
+
+/// Encode `message` as CBOR bytes, reusing the same Debezium JSON shape as the
+/// text encoder so the two formats never drift apart.
+pub(crate) fn message_to_cbor_impl(py: Python, message: &ReplicationMessage) -> PyResult<Vec<u8>> {
+    let value = replication_message_to_json_value(py, message, StringDecodePolicy::Lossy)?;
+    serde_cbor::to_vec(&value)
+        .map_err(|e| PyValueError::new_err(format!("CBOR encoding failed: {}", e)))
+}
+
+/// Decode a `ReplicationMessage` previously produced by [`message_to_cbor_impl`].
+pub(crate) fn message_from_cbor_impl(py: Python, data: &[u8]) -> PyResult<ReplicationMessage> {
+    let value: serde_json::Value = serde_cbor::from_slice(data)
+        .map_err(|e| PyValueError::new_err(format!("malformed CBOR: {}", e)))?;
+    replication_message_from_json(py, &value)
+}
+
+/// Encode `message` as CBOR bytes. Equivalent to `message.cbor()`.
+#[pyfunction]
+pub fn message_to_cbor(py: Python, message: &ReplicationMessage) -> PyResult<Vec<u8>> {
+    message_to_cbor_impl(py, message)
+}
+
+/// Decode a `ReplicationMessage` previously produced by `message_to_cbor`.
+#[pyfunction]
+pub fn message_from_cbor(py: Python, data: Vec<u8>) -> PyResult<ReplicationMessage> {
+    message_from_cbor_impl(py, &data)
+}
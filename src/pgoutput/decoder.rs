@@ -1,28 +1,41 @@
-use bytes::{Buf, Bytes};
-use std::io;
+use super::error::DecodeError;
 use super::messages::*;
+use super::pgvalue::{decode_typed_value, PgValue};
+#[cfg(feature = "python")]
+use super::types::OidTypeRegistry;
+use bytes::{Buf, Bytes};
 
 /// Decoder for pgoutput binary protocol
 pub struct PgOutputDecoder {
     // Cache for relation schemas
     relations: std::collections::HashMap<u32, RelationMessage>,
+    // OID -> conversion mapping used to turn decoded column bytes into typed values
+    #[cfg(feature = "python")]
+    type_registry: OidTypeRegistry,
+    // Set between a `StreamStart` and its matching `StreamStop`: while true,
+    // Insert/Update/Delete/Truncate messages carry a leading xid since they
+    // belong to an in-progress, not-yet-committed transaction.
+    in_stream: bool,
 }
 
 impl PgOutputDecoder {
     pub fn new() -> Self {
         Self {
             relations: std::collections::HashMap::new(),
+            #[cfg(feature = "python")]
+            type_registry: OidTypeRegistry::new(),
+            in_stream: false,
         }
     }
-    
+
     /// Decode a pgoutput message from bytes
-    pub fn decode(&mut self, mut data: Bytes) -> Result<PgOutputMessage, io::Error> {
+    pub fn decode(&mut self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
         if data.is_empty() {
-            return Err(io::Error::new(io::ErrorKind::InvalidData, "Empty message"));
+            return Err(DecodeError::UnexpectedEof);
         }
-        
+
         let msg_type = data.get_u8() as char;
-        
+
         match msg_type {
             'B' => self.decode_begin(data),
             'C' => self.decode_commit(data),
@@ -34,31 +47,37 @@ impl PgOutputDecoder {
             'Y' => self.decode_type(data),
             'O' => self.decode_origin(data),
             'M' => self.decode_message(data),
-            _ => Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("Unknown message type: {}", msg_type),
-            )),
+            'S' => self.decode_stream_start(data),
+            'E' => self.decode_stream_stop(data),
+            'c' => self.decode_stream_commit(data),
+            'A' => self.decode_stream_abort(data),
+            'b' => self.decode_begin_prepare(data),
+            'P' => self.decode_prepare(data),
+            'K' => self.decode_commit_prepared(data),
+            'r' => self.decode_rollback_prepared(data),
+            'p' => self.decode_stream_prepare(data),
+            _ => Err(DecodeError::UnknownMessageType(msg_type)),
         }
     }
-    
-    fn decode_begin(&self, mut data: Bytes) -> Result<PgOutputMessage, io::Error> {
+
+    fn decode_begin(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
         let final_lsn = data.get_u64();
         let timestamp = data.get_i64();
         let xid = data.get_u32();
-        
+
         Ok(PgOutputMessage::Begin(BeginMessage {
             final_lsn,
             timestamp,
             xid,
         }))
     }
-    
-    fn decode_commit(&self, mut data: Bytes) -> Result<PgOutputMessage, io::Error> {
+
+    fn decode_commit(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
         let flags = data.get_u8();
         let commit_lsn = data.get_u64();
         let end_lsn = data.get_u64();
         let timestamp = data.get_i64();
-        
+
         Ok(PgOutputMessage::Commit(CommitMessage {
             flags,
             commit_lsn,
@@ -66,21 +85,25 @@ impl PgOutputDecoder {
             timestamp,
         }))
     }
-    
-    fn decode_relation(&mut self, mut data: Bytes) -> Result<PgOutputMessage, io::Error> {
+
+    fn decode_relation(&mut self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
+        // Relation messages carry the same streamed-xid prefix as Insert/Update/
+        // Delete/Truncate while streaming; RelationMessage itself has no xid
+        // field (it isn't a row change), so just consume the bytes.
+        let _xid = self.read_stream_xid(&mut data);
         let rel_id = data.get_u32();
         let namespace = read_cstring(&mut data)?;
         let name = read_cstring(&mut data)?;
         let replica_identity = data.get_u8();
         let n_columns = data.get_u16();
-        
+
         let mut columns = Vec::new();
         for _ in 0..n_columns {
             let flags = data.get_u8();
             let col_name = read_cstring(&mut data)?;
             let type_id = data.get_u32();
             let type_modifier = data.get_i32();
-            
+
             columns.push(ColumnInfo {
                 flags,
                 name: col_name,
@@ -88,7 +111,7 @@ impl PgOutputDecoder {
                 type_modifier,
             });
         }
-        
+
         let relation = RelationMessage {
             rel_id,
             namespace,
@@ -96,33 +119,36 @@ impl PgOutputDecoder {
             replica_identity,
             columns,
         };
-        
+
         // Cache the relation
         self.relations.insert(rel_id, relation.clone());
-        
+
         Ok(PgOutputMessage::Relation(relation))
     }
-    
-    fn decode_insert(&self, mut data: Bytes) -> Result<PgOutputMessage, io::Error> {
+
+    fn decode_insert(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
+        let xid = self.read_stream_xid(&mut data);
         let rel_id = data.get_u32();
         let tuple_type = data.get_u8();
-        
+
         if tuple_type != b'N' {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("Expected new tuple (N), got: {}", tuple_type as char),
-            ));
+            return Err(DecodeError::UnexpectedTupleType(tuple_type as char));
         }
-        
+
         let tuple = read_tuple_data(&mut data)?;
-        
-        Ok(PgOutputMessage::Insert(InsertMessage { rel_id, tuple }))
+
+        Ok(PgOutputMessage::Insert(InsertMessage {
+            xid,
+            rel_id,
+            tuple,
+        }))
     }
-    
-    fn decode_update(&self, mut data: Bytes) -> Result<PgOutputMessage, io::Error> {
+
+    fn decode_update(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
+        let xid = self.read_stream_xid(&mut data);
         let rel_id = data.get_u32();
         let tuple_type = data.get_u8();
-        
+
         let old_tuple = match tuple_type {
             b'O' | b'K' => {
                 let old = read_tuple_data(&mut data)?;
@@ -130,80 +156,83 @@ impl PgOutputDecoder {
                 Some(old)
             }
             b'N' => None,
-            _ => {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    format!("Unexpected tuple type: {}", tuple_type as char),
-                ))
-            }
+            _ => return Err(DecodeError::UnexpectedTupleType(tuple_type as char)),
         };
-        
+
         let new_tuple = read_tuple_data(&mut data)?;
-        
+
         Ok(PgOutputMessage::Update(UpdateMessage {
+            xid,
             rel_id,
             old_tuple,
             new_tuple,
         }))
     }
-    
-    fn decode_delete(&self, mut data: Bytes) -> Result<PgOutputMessage, io::Error> {
+
+    fn decode_delete(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
+        let xid = self.read_stream_xid(&mut data);
         let rel_id = data.get_u32();
         let tuple_type = data.get_u8();
-        
+
         if tuple_type != b'O' && tuple_type != b'K' {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("Expected old tuple (O/K), got: {}", tuple_type as char),
-            ));
+            return Err(DecodeError::UnexpectedTupleType(tuple_type as char));
         }
-        
+
         let old_tuple = read_tuple_data(&mut data)?;
-        
-        Ok(PgOutputMessage::Delete(DeleteMessage { rel_id, old_tuple }))
+
+        Ok(PgOutputMessage::Delete(DeleteMessage {
+            xid,
+            rel_id,
+            old_tuple,
+        }))
     }
-    
-    fn decode_truncate(&self, mut data: Bytes) -> Result<PgOutputMessage, io::Error> {
+
+    fn decode_truncate(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
+        let xid = self.read_stream_xid(&mut data);
         let n_relations = data.get_u32();
         let options = data.get_u8();
-        
+
         let mut rel_ids = Vec::new();
         for _ in 0..n_relations {
             rel_ids.push(data.get_u32());
         }
-        
-        Ok(PgOutputMessage::Truncate(TruncateMessage { options, rel_ids }))
+
+        Ok(PgOutputMessage::Truncate(TruncateMessage {
+            xid,
+            options,
+            rel_ids,
+        }))
     }
-    
-    fn decode_type(&self, mut data: Bytes) -> Result<PgOutputMessage, io::Error> {
+
+    fn decode_type(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
         let type_id = data.get_u32();
         let namespace = read_cstring(&mut data)?;
         let name = read_cstring(&mut data)?;
-        
+
         Ok(PgOutputMessage::Type(TypeMessage {
             type_id,
             namespace,
             name,
         }))
     }
-    
-    fn decode_origin(&self, mut data: Bytes) -> Result<PgOutputMessage, io::Error> {
+
+    fn decode_origin(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
         let lsn = data.get_u64();
         let name = read_cstring(&mut data)?;
-        
+
         Ok(PgOutputMessage::Origin(OriginMessage { lsn, name }))
     }
-    
-    fn decode_message(&self, mut data: Bytes) -> Result<PgOutputMessage, io::Error> {
+
+    fn decode_message(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
         let flags = data.get_u8();
         let transactional = (flags & 1) != 0;
         let lsn = data.get_u64();
         let prefix = read_cstring(&mut data)?;
         let content_len = data.get_u32() as usize;
-        
+
         let mut content = vec![0u8; content_len];
         data.copy_to_slice(&mut content);
-        
+
         Ok(PgOutputMessage::Message(LogicalMessage {
             transactional,
             lsn,
@@ -211,21 +240,189 @@ impl PgOutputDecoder {
             content,
         }))
     }
-    
+
+    fn decode_stream_start(&mut self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
+        let xid = data.get_u32();
+        let first_segment = data.get_u8() == 1;
+        self.in_stream = true;
+
+        Ok(PgOutputMessage::StreamStart(StreamStartMessage {
+            xid,
+            first_segment,
+        }))
+    }
+
+    fn decode_stream_stop(&mut self, _data: Bytes) -> Result<PgOutputMessage, DecodeError> {
+        self.in_stream = false;
+        Ok(PgOutputMessage::StreamStop(StreamStopMessage))
+    }
+
+    fn decode_stream_commit(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
+        let xid = data.get_u32();
+        let flags = data.get_u8();
+        let commit_lsn = data.get_u64();
+        let end_lsn = data.get_u64();
+        let timestamp = data.get_i64();
+
+        Ok(PgOutputMessage::StreamCommit(StreamCommitMessage {
+            xid,
+            flags,
+            commit_lsn,
+            end_lsn,
+            timestamp,
+        }))
+    }
+
+    fn decode_stream_abort(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
+        let xid = data.get_u32();
+        let sub_xid = data.get_u32();
+
+        Ok(PgOutputMessage::StreamAbort(StreamAbortMessage {
+            xid,
+            sub_xid,
+        }))
+    }
+
+    fn decode_begin_prepare(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
+        let prepare_lsn = data.get_u64();
+        let end_lsn = data.get_u64();
+        let timestamp = data.get_i64();
+        let xid = data.get_u32();
+        let gid = read_cstring(&mut data)?;
+
+        Ok(PgOutputMessage::BeginPrepare(BeginPrepareMessage {
+            prepare_lsn,
+            end_lsn,
+            timestamp,
+            xid,
+            gid,
+        }))
+    }
+
+    fn decode_prepare(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
+        let flags = data.get_u8();
+        let prepare_lsn = data.get_u64();
+        let end_lsn = data.get_u64();
+        let timestamp = data.get_i64();
+        let xid = data.get_u32();
+        let gid = read_cstring(&mut data)?;
+
+        Ok(PgOutputMessage::Prepare(PrepareMessage {
+            flags,
+            prepare_lsn,
+            end_lsn,
+            timestamp,
+            xid,
+            gid,
+        }))
+    }
+
+    fn decode_commit_prepared(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
+        let flags = data.get_u8();
+        let commit_lsn = data.get_u64();
+        let end_lsn = data.get_u64();
+        let timestamp = data.get_i64();
+        let xid = data.get_u32();
+        let gid = read_cstring(&mut data)?;
+
+        Ok(PgOutputMessage::CommitPrepared(CommitPreparedMessage {
+            flags,
+            commit_lsn,
+            end_lsn,
+            timestamp,
+            xid,
+            gid,
+        }))
+    }
+
+    fn decode_rollback_prepared(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
+        let flags = data.get_u8();
+        let prepare_end_lsn = data.get_u64();
+        let end_lsn = data.get_u64();
+        let prepare_timestamp = data.get_i64();
+        let rollback_timestamp = data.get_i64();
+        let xid = data.get_u32();
+        let gid = read_cstring(&mut data)?;
+
+        Ok(PgOutputMessage::RollbackPrepared(RollbackPreparedMessage {
+            flags,
+            prepare_end_lsn,
+            end_lsn,
+            prepare_timestamp,
+            rollback_timestamp,
+            xid,
+            gid,
+        }))
+    }
+
+    fn decode_stream_prepare(&self, mut data: Bytes) -> Result<PgOutputMessage, DecodeError> {
+        let flags = data.get_u8();
+        let prepare_lsn = data.get_u64();
+        let end_lsn = data.get_u64();
+        let timestamp = data.get_i64();
+        let xid = data.get_u32();
+        let gid = read_cstring(&mut data)?;
+
+        Ok(PgOutputMessage::StreamPrepare(StreamPrepareMessage {
+            flags,
+            prepare_lsn,
+            end_lsn,
+            timestamp,
+            xid,
+            gid,
+        }))
+    }
+
+    /// Consume the leading xid that Insert/Update/Delete/Truncate carry while
+    /// decoding an in-progress streamed transaction (between `StreamStart`
+    /// and `StreamStop`); absent otherwise.
+    fn read_stream_xid(&self, data: &mut Bytes) -> Option<u32> {
+        self.in_stream.then(|| data.get_u32())
+    }
+
     pub fn get_relation(&self, rel_id: u32) -> Option<&RelationMessage> {
         self.relations.get(&rel_id)
     }
+
+    /// Decode an Insert/Update/Delete tuple's columns into typed `PgValue`s
+    /// using `rel_id`'s cached column OIDs, instead of raw tuple bytes. One
+    /// entry per column: `None` for a real SQL NULL, otherwise `Some`
+    /// (`PgValue::UnchangedToast` for an unchanged TOAST column). Falls back
+    /// to `PgValue::Raw` per column for unrecognized OIDs, and for every
+    /// column if `rel_id` hasn't been seen via a `Relation` message yet.
+    pub fn decode_typed(&self, rel_id: u32, tuple: &[TupleValue]) -> Vec<Option<PgValue>> {
+        let relation = self.get_relation(rel_id);
+        tuple
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let type_id = relation.and_then(|r| r.columns.get(i)).map(|c| c.type_id);
+                decode_typed_value(value, type_id)
+            })
+            .collect()
+    }
+
+    /// The OID -> type mapping used to convert decoded column bytes into
+    /// typed values. Register custom handlers for extension types here.
+    #[cfg(feature = "python")]
+    pub fn type_registry(&self) -> &OidTypeRegistry {
+        &self.type_registry
+    }
+
+    /// Mutable access to the OID type registry, for registering handlers for
+    /// extension types before or during decoding.
+    #[cfg(feature = "python")]
+    pub fn type_registry_mut(&mut self) -> &mut OidTypeRegistry {
+        &mut self.type_registry
+    }
 }
 
 /// Read a null-terminated C string from bytes
-fn read_cstring(data: &mut Bytes) -> Result<String, io::Error> {
+fn read_cstring(data: &mut Bytes) -> Result<String, DecodeError> {
     let mut bytes = Vec::new();
     loop {
         if data.is_empty() {
-            return Err(io::Error::new(
-                io::ErrorKind::UnexpectedEof,
-                "Unexpected end of data while reading string",
-            ));
+            return Err(DecodeError::UnexpectedEof);
         }
         let byte = data.get_u8();
         if byte == 0 {
@@ -233,38 +430,245 @@ fn read_cstring(data: &mut Bytes) -> Result<String, io::Error> {
         }
         bytes.push(byte);
     }
-    String::from_utf8(bytes)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    Ok(String::from_utf8(bytes)?)
 }
 
 /// Read tuple data (column values) from bytes
-fn read_tuple_data(data: &mut Bytes) -> Result<Vec<Option<Vec<u8>>>, io::Error> {
+fn read_tuple_data(data: &mut Bytes) -> Result<Vec<TupleValue>, DecodeError> {
     let n_columns = data.get_u16();
     let mut tuple = Vec::new();
-    
+
     for _ in 0..n_columns {
         let value_type = data.get_u8() as char;
-        
+
         let value = match value_type {
-            'n' => None, // NULL
-            'u' => None, // UNCHANGED TOAST
+            'n' => TupleValue::Null,
+            'u' => TupleValue::UnchangedToast,
             't' => {
-                // Text/binary data
+                // Text-format data
                 let len = data.get_u32() as usize;
                 let mut bytes = vec![0u8; len];
                 data.copy_to_slice(&mut bytes);
-                Some(bytes)
+                TupleValue::Text(bytes)
             }
-            _ => {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    format!("Unknown tuple value type: {}", value_type),
-                ))
+            'b' => {
+                // Binary-format data (publication created with `binary = true`)
+                let len = data.get_u32() as usize;
+                let mut bytes = vec![0u8; len];
+                data.copy_to_slice(&mut bytes);
+                TupleValue::Binary(bytes)
             }
+            _ => return Err(DecodeError::UnknownTupleValue(value_type)),
         };
-        
+
         tuple.push(value);
     }
-    
+
     Ok(tuple)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_column(value: &[u8]) -> Vec<u8> {
+        let mut out = vec![b't'];
+        out.extend_from_slice(&(value.len() as u32).to_be_bytes());
+        out.extend_from_slice(value);
+        out
+    }
+
+    fn insert_wire(rel_id: u32, columns: &[Vec<u8>]) -> Bytes {
+        let mut out = vec![b'I'];
+        out.extend_from_slice(&rel_id.to_be_bytes());
+        out.push(b'N');
+        out.extend_from_slice(&(columns.len() as u16).to_be_bytes());
+        for column in columns {
+            out.extend_from_slice(column);
+        }
+        Bytes::from(out)
+    }
+
+    #[test]
+    fn decode_insert_outside_stream_has_no_xid() {
+        let wire = insert_wire(7, &[text_column(b"foo")]);
+        let mut decoder = PgOutputDecoder::new();
+
+        match decoder.decode(wire).unwrap() {
+            PgOutputMessage::Insert(insert) => {
+                assert_eq!(insert.xid, None);
+                assert_eq!(insert.rel_id, 7);
+                match &insert.tuple[0] {
+                    TupleValue::Text(bytes) => assert_eq!(bytes, b"foo"),
+                    other => panic!("expected Text, got {:?}", other),
+                }
+            }
+            other => panic!("expected Insert, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_insert_inside_stream_consumes_leading_xid() {
+        let mut decoder = PgOutputDecoder::new();
+        let stream_start = Bytes::from(vec![b'S', 0, 0, 0, 42, 1]);
+        decoder.decode(stream_start).unwrap();
+
+        let mut wire = vec![b'I'];
+        wire.extend_from_slice(&42u32.to_be_bytes()); // streamed xid prefix
+        wire.extend_from_slice(&7u32.to_be_bytes()); // rel_id
+        wire.push(b'N');
+        wire.extend_from_slice(&0u16.to_be_bytes()); // no columns
+
+        match decoder.decode(Bytes::from(wire)).unwrap() {
+            PgOutputMessage::Insert(insert) => {
+                assert_eq!(insert.xid, Some(42));
+                assert_eq!(insert.rel_id, 7);
+            }
+            other => panic!("expected Insert, got {:?}", other),
+        }
+    }
+
+    // Regression test for the bug caught in review: decode_relation must
+    // consume the same streamed-xid prefix as the other row-change decoders,
+    // or the xid bytes get misread as rel_id.
+    #[test]
+    fn decode_relation_inside_stream_consumes_leading_xid() {
+        let mut decoder = PgOutputDecoder::new();
+        let stream_start = Bytes::from(vec![b'S', 0, 0, 0, 42, 1]);
+        decoder.decode(stream_start).unwrap();
+
+        let mut wire = vec![b'R'];
+        wire.extend_from_slice(&42u32.to_be_bytes()); // streamed xid prefix
+        wire.extend_from_slice(&99u32.to_be_bytes()); // rel_id
+        wire.extend_from_slice(b"public\0");
+        wire.extend_from_slice(b"users\0");
+        wire.push(b'd'); // replica_identity
+        wire.extend_from_slice(&0u16.to_be_bytes()); // no columns
+
+        match decoder.decode(Bytes::from(wire)).unwrap() {
+            PgOutputMessage::Relation(relation) => {
+                assert_eq!(relation.rel_id, 99);
+                assert_eq!(relation.namespace, "public");
+                assert_eq!(relation.name, "users");
+            }
+            other => panic!("expected Relation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_relation_outside_stream_has_no_xid_prefix() {
+        let mut decoder = PgOutputDecoder::new();
+
+        let mut wire = vec![b'R'];
+        wire.extend_from_slice(&99u32.to_be_bytes()); // rel_id, no xid prefix
+        wire.extend_from_slice(b"public\0");
+        wire.extend_from_slice(b"users\0");
+        wire.push(b'd');
+        wire.extend_from_slice(&0u16.to_be_bytes());
+
+        match decoder.decode(Bytes::from(wire)).unwrap() {
+            PgOutputMessage::Relation(relation) => {
+                assert_eq!(relation.rel_id, 99);
+            }
+            other => panic!("expected Relation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn tuple_data_distinguishes_null_from_unchanged_toast() {
+        let mut wire = vec![b'I'];
+        wire.extend_from_slice(&1u32.to_be_bytes());
+        wire.push(b'N');
+        wire.extend_from_slice(&2u16.to_be_bytes());
+        wire.push(b'n'); // NULL
+        wire.push(b'u'); // unchanged TOAST
+        let mut decoder = PgOutputDecoder::new();
+
+        match decoder.decode(Bytes::from(wire)).unwrap() {
+            PgOutputMessage::Insert(insert) => {
+                assert!(matches!(insert.tuple[0], TupleValue::Null));
+                assert!(matches!(insert.tuple[1], TupleValue::UnchangedToast));
+                assert_eq!(decode_typed_value(&insert.tuple[0], Some(23)), None);
+                assert_eq!(
+                    decode_typed_value(&insert.tuple[1], Some(23)),
+                    Some(PgValue::UnchangedToast)
+                );
+            }
+            other => panic!("expected Insert, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn tuple_data_reads_binary_column() {
+        let mut wire = vec![b'I'];
+        wire.extend_from_slice(&1u32.to_be_bytes());
+        wire.push(b'N');
+        wire.extend_from_slice(&1u16.to_be_bytes());
+        wire.push(b'b');
+        wire.extend_from_slice(&3u32.to_be_bytes());
+        wire.extend_from_slice(&[0xDE, 0xAD, 0xBE]);
+        let mut decoder = PgOutputDecoder::new();
+
+        match decoder.decode(Bytes::from(wire)).unwrap() {
+            PgOutputMessage::Insert(insert) => match &insert.tuple[0] {
+                TupleValue::Binary(bytes) => assert_eq!(bytes, &[0xDE, 0xAD, 0xBE]),
+                other => panic!("expected Binary, got {:?}", other),
+            },
+            other => panic!("expected Insert, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_begin_prepare_reads_gid() {
+        let mut wire = vec![b'b'];
+        wire.extend_from_slice(&1u64.to_be_bytes()); // prepare_lsn
+        wire.extend_from_slice(&2u64.to_be_bytes()); // end_lsn
+        wire.extend_from_slice(&3i64.to_be_bytes()); // timestamp
+        wire.extend_from_slice(&4u32.to_be_bytes()); // xid
+        wire.extend_from_slice(b"gid-123\0");
+        let mut decoder = PgOutputDecoder::new();
+
+        match decoder.decode(Bytes::from(wire)).unwrap() {
+            PgOutputMessage::BeginPrepare(msg) => {
+                assert_eq!(msg.xid, 4);
+                assert_eq!(msg.gid, "gid-123");
+            }
+            other => panic!("expected BeginPrepare, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_stream_commit_and_abort() {
+        let mut commit_wire = vec![b'c'];
+        commit_wire.extend_from_slice(&42u32.to_be_bytes()); // xid
+        commit_wire.push(0); // flags
+        commit_wire.extend_from_slice(&1u64.to_be_bytes()); // commit_lsn
+        commit_wire.extend_from_slice(&2u64.to_be_bytes()); // end_lsn
+        commit_wire.extend_from_slice(&3i64.to_be_bytes()); // timestamp
+        let mut decoder = PgOutputDecoder::new();
+
+        match decoder.decode(Bytes::from(commit_wire)).unwrap() {
+            PgOutputMessage::StreamCommit(msg) => assert_eq!(msg.xid, 42),
+            other => panic!("expected StreamCommit, got {:?}", other),
+        }
+
+        let mut abort_wire = vec![b'A'];
+        abort_wire.extend_from_slice(&42u32.to_be_bytes());
+        abort_wire.extend_from_slice(&0u32.to_be_bytes());
+
+        match decoder.decode(Bytes::from(abort_wire)).unwrap() {
+            PgOutputMessage::StreamAbort(msg) => {
+                assert_eq!(msg.xid, 42);
+                assert_eq!(msg.sub_xid, 0);
+            }
+            other => panic!("expected StreamAbort, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_unknown_message_type_is_an_error() {
+        let mut decoder = PgOutputDecoder::new();
+        let err = decoder.decode(Bytes::from(vec![b'Z'])).unwrap_err();
+        assert!(matches!(err, DecodeError::UnknownMessageType('Z')));
+    }
+}
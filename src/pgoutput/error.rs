@@ -0,0 +1,41 @@
+use std::fmt;
+
+/// Why a pgoutput message failed to decode.
+///
+/// Replaces a bare `io::Error` so callers can distinguish a truncated buffer
+/// from an unrecognized message/tuple-value type instead of matching on a
+/// generic `InvalidData` kind.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// Ran out of bytes partway through a message.
+    UnexpectedEof,
+    /// The leading message-type byte wasn't one pgoutput defines.
+    UnknownMessageType(char),
+    /// A tuple's value-type byte wasn't `'n'`, `'u'`, `'t'`, or `'b'`.
+    UnknownTupleValue(char),
+    /// An Insert/Update/Delete message's tuple-type byte wasn't valid for
+    /// that message (e.g. Update expected `'O'`/`'K'`/`'N'`).
+    UnexpectedTupleType(char),
+    /// A `cstring` field wasn't valid UTF-8.
+    InvalidUtf8(std::string::FromUtf8Error),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEof => write!(f, "unexpected end of data"),
+            DecodeError::UnknownMessageType(c) => write!(f, "unknown message type: {}", c),
+            DecodeError::UnknownTupleValue(c) => write!(f, "unknown tuple value type: {}", c),
+            DecodeError::UnexpectedTupleType(c) => write!(f, "unexpected tuple type: {}", c),
+            DecodeError::InvalidUtf8(e) => write!(f, "invalid UTF-8: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<std::string::FromUtf8Error> for DecodeError {
+    fn from(e: std::string::FromUtf8Error) -> Self {
+        DecodeError::InvalidUtf8(e)
+    }
+}
@@ -1,47 +1,60 @@
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
-use std::collections::HashMap;
-use serde_json::ser::PrettyFormatter;
+#[cfg(feature = "python")]
 use serde::Serialize;
+#[cfg(feature = "python")]
+use serde_json::ser::PrettyFormatter;
+#[cfg(feature = "python")]
+use std::collections::HashMap;
 
 /// Represents a decoded replication message in Debezium format
+#[cfg(feature = "python")]
 #[derive(Debug, Clone)]
 #[pyclass]
 pub struct ReplicationMessage {
     /// State of the row before the event (for UPDATE and DELETE)
     #[pyo3(get)]
     pub before: Option<PyObject>,
-    
+
     /// State of the row after the event (for INSERT and UPDATE)
     #[pyo3(get)]
     pub after: Option<PyObject>,
-    
+
     /// Source metadata
     #[pyo3(get)]
     pub source: PyObject,
-    
+
     /// Operation type: "c" (create), "u" (update), "d" (delete), "r" (read/snapshot)
     #[pyo3(get)]
     pub op: String,
-    
+
     /// Timestamp when connector processed the event (milliseconds since epoch)
     #[pyo3(get)]
     pub ts_ms: i64,
-    
+
     /// Timestamp when connector processed the event (microseconds since epoch)
     #[pyo3(get)]
     pub ts_us: Option<i64>,
-    
+
     /// Timestamp when connector processed the event (nanoseconds since epoch)
     #[pyo3(get)]
     pub ts_ns: Option<i64>,
 }
 
+#[cfg(feature = "python")]
 #[pymethods]
 impl ReplicationMessage {
-    fn json(&self, py: Python, indent: Option<usize>) -> PyResult<String> {
-        to_debezium_json_impl(py, self, indent)
+    #[pyo3(signature = (indent=None, string_policy="lossy"))]
+    fn json(&self, py: Python, indent: Option<usize>, string_policy: &str) -> PyResult<String> {
+        to_debezium_json_impl(py, self, indent, StringDecodePolicy::parse(string_policy)?)
     }
-    
+
+    /// Encode this message as CBOR bytes using the same Debezium JSON shape as
+    /// [`ReplicationMessage::json`], for compact binary change capture.
+    fn cbor(&self, py: Python) -> PyResult<Vec<u8>> {
+        super::cbor::message_to_cbor_impl(py, self)
+    }
+
     fn __repr__(&self) -> String {
         format!("ReplicationMessage(op={})", self.op)
     }
@@ -82,31 +95,62 @@ pub struct ColumnInfo {
     pub type_modifier: i32,
 }
 
+/// A single column value from a decoded tuple (`INSERT`/`UPDATE`/`DELETE`).
+///
+/// pgoutput distinguishes a real SQL `NULL` from a TOASTed column that wasn't
+/// changed by the statement and so wasn't sent at all (`'u'`); collapsing both
+/// to `None` would make an unchanged TOAST value look like it was nulled out.
+#[derive(Debug, Clone)]
+pub enum TupleValue {
+    /// Column is SQL NULL.
+    Null,
+    /// Column is TOASTed and unchanged; its value wasn't sent by the server
+    /// (avoidable with `REPLICA IDENTITY FULL`).
+    UnchangedToast,
+    /// Column value sent in pgoutput's text format.
+    Text(Vec<u8>),
+    /// Column value sent in PostgreSQL's binary wire format (negotiated via
+    /// the `binary` publication option).
+    Binary(Vec<u8>),
+}
+
 /// Insert message
 #[derive(Debug, Clone)]
 pub struct InsertMessage {
+    /// Transaction ID, present when this message was sent as part of an
+    /// in-progress streamed transaction (see `StreamStartMessage`).
+    pub xid: Option<u32>,
     pub rel_id: u32,
-    pub tuple: Vec<Option<Vec<u8>>>,
+    pub tuple: Vec<TupleValue>,
 }
 
 /// Update message
 #[derive(Debug, Clone)]
 pub struct UpdateMessage {
+    /// Transaction ID, present when this message was sent as part of an
+    /// in-progress streamed transaction (see `StreamStartMessage`).
+    pub xid: Option<u32>,
     pub rel_id: u32,
-    pub old_tuple: Option<Vec<Option<Vec<u8>>>>,
-    pub new_tuple: Vec<Option<Vec<u8>>>,
+    pub old_tuple: Option<Vec<TupleValue>>,
+    pub new_tuple: Vec<TupleValue>,
 }
 
 /// Delete message
 #[derive(Debug, Clone)]
 pub struct DeleteMessage {
+    /// Transaction ID, present when this message was sent as part of an
+    /// in-progress streamed transaction (see `StreamStartMessage`).
+    pub xid: Option<u32>,
     pub rel_id: u32,
-    pub old_tuple: Vec<Option<Vec<u8>>>,
+    pub old_tuple: Vec<TupleValue>,
 }
 
 /// Truncate message
 #[derive(Debug, Clone)]
 pub struct TruncateMessage {
+    /// Transaction ID, present when this message was sent as part of an
+    /// in-progress streamed transaction (see `StreamStartMessage`).
+    pub xid: Option<u32>,
     pub options: u8,
     pub rel_ids: Vec<u32>,
 }
@@ -135,6 +179,102 @@ pub struct LogicalMessage {
     pub content: Vec<u8>,
 }
 
+/// Marks the start of a chunk of changes from an in-progress (not yet
+/// committed) transaction, sent only when streaming is enabled. `first_segment`
+/// is set on the first chunk for `xid`.
+#[derive(Debug, Clone)]
+pub struct StreamStartMessage {
+    pub xid: u32,
+    pub first_segment: bool,
+}
+
+/// Marks the end of a chunk of streamed changes. More chunks for the same
+/// `xid` may follow in later `StreamStart`/`StreamStop` pairs.
+#[derive(Debug, Clone)]
+pub struct StreamStopMessage;
+
+/// Commits a previously streamed transaction. Equivalent to `CommitMessage`
+/// but carries `xid` since the matching `BeginMessage` may have arrived in an
+/// earlier, already-flushed chunk.
+#[derive(Debug, Clone)]
+pub struct StreamCommitMessage {
+    pub xid: u32,
+    pub flags: u8,
+    pub commit_lsn: u64,
+    pub end_lsn: u64,
+    pub timestamp: i64,
+}
+
+/// Aborts a previously streamed transaction (or subtransaction `sub_xid`
+/// within it), discarding any buffered changes for it.
+#[derive(Debug, Clone)]
+pub struct StreamAbortMessage {
+    pub xid: u32,
+    pub sub_xid: u32,
+}
+
+/// Begins a two-phase-commit transaction that is being prepared (`PREPARE
+/// TRANSACTION`), carrying the user-supplied `gid` that later identifies it
+/// to `PrepareMessage`/`CommitPreparedMessage`/`RollbackPreparedMessage`.
+#[derive(Debug, Clone)]
+pub struct BeginPrepareMessage {
+    pub prepare_lsn: u64,
+    pub end_lsn: u64,
+    pub timestamp: i64,
+    pub xid: u32,
+    pub gid: String,
+}
+
+/// Marks a two-phase-commit transaction as prepared; it stays uncommitted
+/// until a matching `CommitPreparedMessage` or `RollbackPreparedMessage`.
+#[derive(Debug, Clone)]
+pub struct PrepareMessage {
+    pub flags: u8,
+    pub prepare_lsn: u64,
+    pub end_lsn: u64,
+    pub timestamp: i64,
+    pub xid: u32,
+    pub gid: String,
+}
+
+/// Commits a previously prepared two-phase-commit transaction (`COMMIT
+/// PREPARED`).
+#[derive(Debug, Clone)]
+pub struct CommitPreparedMessage {
+    pub flags: u8,
+    pub commit_lsn: u64,
+    pub end_lsn: u64,
+    pub timestamp: i64,
+    pub xid: u32,
+    pub gid: String,
+}
+
+/// Rolls back a previously prepared two-phase-commit transaction (`ROLLBACK
+/// PREPARED`), discarding any buffered changes for it.
+#[derive(Debug, Clone)]
+pub struct RollbackPreparedMessage {
+    pub flags: u8,
+    pub prepare_end_lsn: u64,
+    pub end_lsn: u64,
+    pub prepare_timestamp: i64,
+    pub rollback_timestamp: i64,
+    pub xid: u32,
+    pub gid: String,
+}
+
+/// Prepares a previously streamed (in-progress) transaction for two-phase
+/// commit. Equivalent to `PrepareMessage` but for a transaction whose changes
+/// arrived via `StreamStart`/`StreamStop` chunks rather than in one piece.
+#[derive(Debug, Clone)]
+pub struct StreamPrepareMessage {
+    pub flags: u8,
+    pub prepare_lsn: u64,
+    pub end_lsn: u64,
+    pub timestamp: i64,
+    pub xid: u32,
+    pub gid: String,
+}
+
 /// Enum for all pgoutput message types
 #[derive(Debug, Clone)]
 pub enum PgOutputMessage {
@@ -148,46 +288,147 @@ pub enum PgOutputMessage {
     Type(TypeMessage),
     Origin(OriginMessage),
     Message(LogicalMessage),
+    StreamStart(StreamStartMessage),
+    StreamStop(StreamStopMessage),
+    StreamCommit(StreamCommitMessage),
+    StreamAbort(StreamAbortMessage),
+    BeginPrepare(BeginPrepareMessage),
+    Prepare(PrepareMessage),
+    CommitPrepared(CommitPreparedMessage),
+    RollbackPrepared(RollbackPreparedMessage),
+    StreamPrepare(StreamPrepareMessage),
+}
+
+/// Controls how invalid UTF-8 bytes and lone UTF-16 surrogates are handled when a
+/// column value is converted to JSON.
+#[cfg(feature = "python")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringDecodePolicy {
+    /// Replace invalid bytes/surrogates with U+FFFD instead of failing.
+    Lossy,
+    /// Return a `PyValueError` naming the offending column.
+    Strict,
+}
+
+#[cfg(feature = "python")]
+impl Default for StringDecodePolicy {
+    fn default() -> Self {
+        StringDecodePolicy::Lossy
+    }
+}
+
+#[cfg(feature = "python")]
+impl StringDecodePolicy {
+    fn parse(s: &str) -> PyResult<Self> {
+        match s {
+            "lossy" => Ok(StringDecodePolicy::Lossy),
+            "strict" => Ok(StringDecodePolicy::Strict),
+            other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "unknown string decode policy '{}' (expected \"lossy\" or \"strict\")",
+                other
+            ))),
+        }
+    }
+}
+
+/// Decode raw bytes to a `String` according to `policy`, naming `field` in the error
+/// message if `policy` is `Strict` and the bytes aren't valid UTF-8.
+#[cfg(feature = "python")]
+fn decode_bytes(bytes: &[u8], policy: StringDecodePolicy, field: Option<&str>) -> PyResult<String> {
+    match policy {
+        StringDecodePolicy::Lossy => Ok(String::from_utf8_lossy(bytes).into_owned()),
+        StringDecodePolicy::Strict => String::from_utf8(bytes.to_vec()).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "invalid UTF-8 in column '{}': {}",
+                field.unwrap_or("<unknown>"),
+                e
+            ))
+        }),
+    }
 }
 
 // Helper function to convert PyObject to JSON value
-fn py_to_json(py: Python, obj: &PyObject) -> serde_json::Value {
+#[cfg(feature = "python")]
+fn py_to_json(
+    py: Python,
+    obj: &PyObject,
+    policy: StringDecodePolicy,
+    field: Option<&str>,
+) -> PyResult<serde_json::Value> {
     if obj.is_none(py) {
-        return serde_json::Value::Null;
+        return Ok(serde_json::Value::Null);
     }
-    
+
     if let Ok(dict) = obj.extract::<HashMap<String, PyObject>>(py) {
         let mut map = serde_json::Map::new();
         for (key, value) in dict {
-            map.insert(key, py_to_json(py, &value));
+            let json_value = py_to_json(py, &value, policy, Some(&key))?;
+            map.insert(key, json_value);
         }
-        return serde_json::Value::Object(map);
+        return Ok(serde_json::Value::Object(map));
     }
-    
+
     if let Ok(s) = obj.extract::<String>(py) {
-        return serde_json::Value::String(s);
+        return Ok(serde_json::Value::String(s));
+    }
+
+    // `str::extract` fails for a Python `str` containing a lone UTF-16 surrogate
+    // (e.g. produced via `surrogateescape`), since Rust's `String` can't represent
+    // one directly. Round-trip through `surrogatepass` bytes so the policy below
+    // can replace or reject it.
+    if let Ok(py_str) = obj.as_ref(py).downcast::<pyo3::types::PyString>() {
+        let raw: Vec<u8> = py_str
+            .call_method1("encode", ("utf-8", "surrogatepass"))?
+            .extract()?;
+        return Ok(serde_json::Value::String(decode_bytes(
+            &raw, policy, field,
+        )?));
+    }
+
+    if let Ok(bytes) = obj.extract::<Vec<u8>>(py) {
+        return Ok(serde_json::Value::String(decode_bytes(
+            &bytes, policy, field,
+        )?));
     }
+
     if let Ok(i) = obj.extract::<i64>(py) {
-        return serde_json::Value::Number(i.into());
+        return Ok(serde_json::Value::Number(i.into()));
     }
     if let Ok(f) = obj.extract::<f64>(py) {
         if let Some(num) = serde_json::Number::from_f64(f) {
-            return serde_json::Value::Number(num);
+            return Ok(serde_json::Value::Number(num));
         }
     }
     if let Ok(b) = obj.extract::<bool>(py) {
-        return serde_json::Value::Bool(b);
+        return Ok(serde_json::Value::Bool(b));
     }
-    
-    serde_json::Value::Null
+
+    Ok(serde_json::Value::Null)
 }
 
-/// Internal implementation for converting ReplicationMessage to Debezium JSON
-fn to_debezium_json_impl(py: Python, message: &ReplicationMessage, indent: Option<usize>) -> PyResult<String> {
-    let before_json = message.before.as_ref().map(|b| py_to_json(py, b)).unwrap_or(serde_json::Value::Null);
-    let after_json = message.after.as_ref().map(|a| py_to_json(py, a)).unwrap_or(serde_json::Value::Null);
-    let source_json = py_to_json(py, &message.source);
-    
+/// Build the Debezium JSON shape for `message` as a `serde_json::Value`, without
+/// serializing it to text. Shared by the text (`to_debezium_json_impl`) and binary
+/// (CBOR) encodings so both stay in sync.
+#[cfg(feature = "python")]
+pub(crate) fn replication_message_to_json_value(
+    py: Python,
+    message: &ReplicationMessage,
+    string_policy: StringDecodePolicy,
+) -> PyResult<serde_json::Value> {
+    let before_json = message
+        .before
+        .as_ref()
+        .map(|b| py_to_json(py, b, string_policy, None))
+        .transpose()?
+        .unwrap_or(serde_json::Value::Null);
+    let after_json = message
+        .after
+        .as_ref()
+        .map(|a| py_to_json(py, a, string_policy, None))
+        .transpose()?
+        .unwrap_or(serde_json::Value::Null);
+    let source_json = py_to_json(py, &message.source, string_policy, None)?;
+
     let mut obj = serde_json::json!({
         "op": message.op,
         "before": before_json,
@@ -195,14 +436,27 @@ fn to_debezium_json_impl(py: Python, message: &ReplicationMessage, indent: Optio
         "source": source_json,
         "ts_ms": message.ts_ms,
     });
-    
+
     if let Some(ts_us) = message.ts_us {
         obj["ts_us"] = serde_json::json!(ts_us);
     }
     if let Some(ts_ns) = message.ts_ns {
         obj["ts_ns"] = serde_json::json!(ts_ns);
     }
-    
+
+    Ok(obj)
+}
+
+/// Internal implementation for converting ReplicationMessage to Debezium JSON
+#[cfg(feature = "python")]
+pub(crate) fn to_debezium_json_impl(
+    py: Python,
+    message: &ReplicationMessage,
+    indent: Option<usize>,
+    string_policy: StringDecodePolicy,
+) -> PyResult<String> {
+    let obj = replication_message_to_json_value(py, message, string_policy)?;
+
     let json_str = if let Some(indent_size) = indent {
         // Create custom formatter with specified indentation
         let indent_bytes = vec![b' '; indent_size];
@@ -212,27 +466,51 @@ fn to_debezium_json_impl(py: Python, message: &ReplicationMessage, indent: Optio
             PrettyFormatter::with_indent(&indent_bytes),
         );
         obj.serialize(&mut ser).map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("JSON serialization failed: {}", e))
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "JSON serialization failed: {}",
+                e
+            ))
         })?;
         String::from_utf8(buf).map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("UTF-8 conversion failed: {}", e))
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "UTF-8 conversion failed: {}",
+                e
+            ))
         })?
     } else {
         // Compact JSON
         serde_json::to_string(&obj).map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("JSON serialization failed: {}", e))
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "JSON serialization failed: {}",
+                e
+            ))
         })?
     };
-    
+
     Ok(json_str)
 }
 
 /// Convert a ReplicationMessage to Debezium-compatible JSON string.
-/// 
+///
+/// `string_policy` controls how invalid UTF-8 bytes and lone UTF-16 surrogates in
+/// column values are handled: `"lossy"` (default) replaces them with U+FFFD,
+/// `"strict"` raises a `ValueError` naming the offending column.
+///
 /// This is a standalone function that can be called from Python as:
 /// `message_to_debezium_json(message, indent=2)`
+#[cfg(feature = "python")]
 #[pyfunction]
-#[pyo3(signature = (message, indent=Some(2)))]
-pub fn message_to_debezium_json(py: Python, message: &ReplicationMessage, indent: Option<usize>) -> PyResult<String> {
-    to_debezium_json_impl(py, message, indent)
+#[pyo3(signature = (message, indent=Some(2), string_policy="lossy"))]
+pub fn message_to_debezium_json(
+    py: Python,
+    message: &ReplicationMessage,
+    indent: Option<usize>,
+    string_policy: &str,
+) -> PyResult<String> {
+    to_debezium_json_impl(
+        py,
+        message,
+        indent,
+        StringDecodePolicy::parse(string_policy)?,
+    )
 }
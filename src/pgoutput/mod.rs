@@ -1,7 +1,21 @@
+#[cfg(feature = "python")]
+pub mod cbor;
 pub mod decoder;
+pub mod error;
 pub mod messages;
+#[cfg(feature = "python")]
+pub mod ndjson;
+pub mod pgvalue;
+#[cfg(feature = "python")]
 pub mod types;
 
+#[cfg(feature = "python")]
+pub use cbor::*;
 pub use decoder::*;
+pub use error::*;
 pub use messages::*;
+#[cfg(feature = "python")]
+pub use ndjson::*;
+pub use pgvalue::*;
+#[cfg(feature = "python")]
 pub use types::*;
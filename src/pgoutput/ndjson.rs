@@ -0,0 +1,139 @@
+//! NDJSON reading/writing for `ReplicationMessage`, gated behind the `python`
+//! feature since `ReplicationMessage`'s fields are `PyObject`s.
+#![cfg(feature = "python")]
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+
+use super::messages::{to_debezium_json_impl, ReplicationMessage, StringDecodePolicy};
+use super::types::json_to_py;
+
+/// Writes `ReplicationMessage`s as newline-delimited JSON: one compact object per line,
+/// no outer array, flushed after every record so downstream readers can tail the stream.
+pub struct DebeziumNdjsonWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> DebeziumNdjsonWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Serialize `message` as compact JSON followed by a single `\n`. Invalid UTF-8
+    /// or lone surrogates in column values are replaced with U+FFFD rather than
+    /// aborting the stream.
+    pub fn write_message(&mut self, py: Python, message: &ReplicationMessage) -> PyResult<()> {
+        let json = to_debezium_json_impl(py, message, None, StringDecodePolicy::Lossy)?;
+        self.inner
+            .write_all(json.as_bytes())
+            .map_err(ndjson_io_err)?;
+        self.inner.write_all(b"\n").map_err(ndjson_io_err)?;
+        self.inner.flush().map_err(ndjson_io_err)
+    }
+}
+
+/// Reads `ReplicationMessage`s back from newline-delimited JSON produced by
+/// [`DebeziumNdjsonWriter`].
+pub struct DebeziumNdjsonReader<R: BufRead> {
+    inner: R,
+}
+
+impl<R: BufRead> DebeziumNdjsonReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// Read the next non-empty line and parse it back into a `ReplicationMessage`.
+    ///
+    /// Returns `Ok(None)` at EOF. A malformed line surfaces as a recoverable
+    /// `PyValueError` rather than aborting the stream, so callers can skip it and
+    /// keep reading.
+    pub fn read_message(&mut self, py: Python) -> PyResult<Option<ReplicationMessage>> {
+        loop {
+            let mut line = String::new();
+            let n = self.inner.read_line(&mut line).map_err(ndjson_io_err)?;
+            if n == 0 {
+                return Ok(None);
+            }
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                continue;
+            }
+            return replication_message_from_json_str(py, line).map(Some);
+        }
+    }
+}
+
+fn ndjson_io_err(e: io::Error) -> PyErr {
+    PyValueError::new_err(format!("NDJSON I/O error: {}", e))
+}
+
+fn replication_message_from_json_str(py: Python, line: &str) -> PyResult<ReplicationMessage> {
+    let value: serde_json::Value = serde_json::from_str(line)
+        .map_err(|e| PyValueError::new_err(format!("malformed NDJSON line: {}", e)))?;
+    replication_message_from_json(py, &value)
+}
+
+pub(crate) fn replication_message_from_json(
+    py: Python,
+    value: &serde_json::Value,
+) -> PyResult<ReplicationMessage> {
+    let op = value
+        .get("op")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    let before = match value.get("before") {
+        Some(serde_json::Value::Null) | None => None,
+        Some(v) => Some(json_to_py(py, v)?),
+    };
+    let after = match value.get("after") {
+        Some(serde_json::Value::Null) | None => None,
+        Some(v) => Some(json_to_py(py, v)?),
+    };
+    let source = json_to_py(py, value.get("source").unwrap_or(&serde_json::Value::Null))?;
+
+    let ts_ms = value.get("ts_ms").and_then(|v| v.as_i64()).unwrap_or(0);
+    let ts_us = value.get("ts_us").and_then(|v| v.as_i64());
+    let ts_ns = value.get("ts_ns").and_then(|v| v.as_i64());
+
+    Ok(ReplicationMessage {
+        before,
+        after,
+        source,
+        op,
+        ts_ms,
+        ts_us,
+        ts_ns,
+    })
+}
+
+/// Write `messages` to `path` as NDJSON (one compact JSON object per line).
+#[pyfunction]
+pub fn write_ndjson(
+    py: Python,
+    messages: Vec<PyRef<ReplicationMessage>>,
+    path: String,
+) -> PyResult<()> {
+    let file = File::create(&path).map_err(ndjson_io_err)?;
+    let mut writer = DebeziumNdjsonWriter::new(file);
+    for message in messages {
+        writer.write_message(py, &message)?;
+    }
+    Ok(())
+}
+
+/// Read all `ReplicationMessage`s from an NDJSON file at `path`.
+#[pyfunction]
+pub fn read_ndjson(py: Python, path: String) -> PyResult<Vec<ReplicationMessage>> {
+    let file = File::open(&path).map_err(ndjson_io_err)?;
+    let mut reader = DebeziumNdjsonReader::new(BufReader::new(file));
+    let mut messages = Vec::new();
+    while let Some(message) = reader.read_message(py)? {
+        messages.push(message);
+    }
+    Ok(messages)
+}
@@ -0,0 +1,148 @@
+use super::messages::TupleValue;
+
+/// A column value decoded into a native Rust type, keyed off the column's
+/// PostgreSQL OID as cached in a `RelationMessage`. This is the Rust-facing
+/// counterpart to `OidTypeRegistry::convert` (which targets `PyObject`), for
+/// consumers that want typed values without going through PyO3 — mirroring
+/// the `types::{Kind, Type}` conversion machinery in the wider postgres
+/// ecosystem.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PgValue {
+    Bool(bool),
+    Int2(i16),
+    Int4(i32),
+    Int8(i64),
+    Float4(f32),
+    Float8(f64),
+    Numeric(String),
+    Text(String),
+    Bytea(Vec<u8>),
+    Date(String),
+    Timestamp(String),
+    Timestamptz(String),
+    Uuid(String),
+    Json(String),
+    /// Column is TOASTed and unchanged; its value wasn't sent by the server.
+    UnchangedToast,
+    /// OID not recognized, or the column was sent in binary format (OID-driven
+    /// text parsing below doesn't apply): the raw bytes as received.
+    Raw(Vec<u8>),
+}
+
+/// Decode `value` into a `PgValue` using `type_id` (the column's OID, `None`
+/// if the relation's schema hasn't been cached yet). A real SQL `NULL`
+/// decodes to `None`; everything else decodes to `Some`.
+pub(crate) fn decode_typed_value(value: &TupleValue, type_id: Option<u32>) -> Option<PgValue> {
+    match value {
+        TupleValue::Null => None,
+        TupleValue::UnchangedToast => Some(PgValue::UnchangedToast),
+        TupleValue::Binary(bytes) => Some(PgValue::Raw(bytes.clone())),
+        TupleValue::Text(bytes) => Some(match type_id {
+            Some(16) => PgValue::Bool(matches!(as_text(bytes), "t" | "true" | "1")),
+            Some(21) => PgValue::Int2(parse_or_default(bytes)),
+            Some(23) => PgValue::Int4(parse_or_default(bytes)),
+            Some(20) => PgValue::Int8(parse_or_default(bytes)),
+            Some(700) => PgValue::Float4(parse_or_default(bytes)),
+            Some(701) => PgValue::Float8(parse_or_default(bytes)),
+            Some(1700) => PgValue::Numeric(as_text(bytes).to_string()),
+            Some(25) | Some(1043) => PgValue::Text(as_text(bytes).to_string()),
+            Some(17) => PgValue::Bytea(decode_bytea_hex(bytes)),
+            Some(1082) => PgValue::Date(as_text(bytes).to_string()),
+            Some(1114) => PgValue::Timestamp(as_text(bytes).to_string()),
+            Some(1184) => PgValue::Timestamptz(as_text(bytes).to_string()),
+            Some(2950) => PgValue::Uuid(as_text(bytes).to_string()),
+            Some(114) | Some(3802) => PgValue::Json(as_text(bytes).to_string()),
+            _ => PgValue::Raw(bytes.clone()),
+        }),
+    }
+}
+
+fn as_text(bytes: &[u8]) -> &str {
+    std::str::from_utf8(bytes).unwrap_or("")
+}
+
+fn parse_or_default<T: std::str::FromStr + Default>(bytes: &[u8]) -> T {
+    as_text(bytes).parse().unwrap_or_default()
+}
+
+/// bytea's text format is a `\x`-prefixed hex string; fall back to the raw
+/// bytes if that's not what we got.
+fn decode_bytea_hex(bytes: &[u8]) -> Vec<u8> {
+    match as_text(bytes).strip_prefix("\\x") {
+        Some(hex_str) => hex::decode(hex_str).unwrap_or_else(|_| bytes.to_vec()),
+        None => bytes.to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(bytes: &[u8]) -> TupleValue {
+        TupleValue::Text(bytes.to_vec())
+    }
+
+    #[test]
+    fn null_decodes_to_none() {
+        assert_eq!(decode_typed_value(&TupleValue::Null, Some(23)), None);
+    }
+
+    #[test]
+    fn unchanged_toast_decodes_regardless_of_type_id() {
+        assert_eq!(
+            decode_typed_value(&TupleValue::UnchangedToast, None),
+            Some(PgValue::UnchangedToast)
+        );
+    }
+
+    #[test]
+    fn binary_format_always_decodes_to_raw() {
+        let value = TupleValue::Binary(vec![1, 2, 3]);
+        // Binary format is negotiated per-column independent of OID; even a
+        // recognized OID (23 = int4) can't be text-parsed here.
+        assert_eq!(
+            decode_typed_value(&value, Some(23)),
+            Some(PgValue::Raw(vec![1, 2, 3]))
+        );
+    }
+
+    #[test]
+    fn known_oids_decode_to_their_typed_variant() {
+        assert_eq!(
+            decode_typed_value(&text(b"t"), Some(16)),
+            Some(PgValue::Bool(true))
+        );
+        assert_eq!(
+            decode_typed_value(&text(b"42"), Some(23)),
+            Some(PgValue::Int4(42))
+        );
+        assert_eq!(
+            decode_typed_value(&text(b"hello"), Some(25)),
+            Some(PgValue::Text("hello".to_string()))
+        );
+        assert_eq!(
+            decode_typed_value(&text(b"\\xdeadbeef"), Some(17)),
+            Some(PgValue::Bytea(vec![0xDE, 0xAD, 0xBE, 0xEF]))
+        );
+    }
+
+    #[test]
+    fn unknown_oid_falls_back_to_raw() {
+        assert_eq!(
+            decode_typed_value(&text(b"whatever"), Some(999_999)),
+            Some(PgValue::Raw(b"whatever".to_vec()))
+        );
+        assert_eq!(
+            decode_typed_value(&text(b"whatever"), None),
+            Some(PgValue::Raw(b"whatever".to_vec()))
+        );
+    }
+
+    #[test]
+    fn bytea_without_hex_prefix_falls_back_to_raw_bytes() {
+        assert_eq!(
+            decode_typed_value(&text(b"not-hex"), Some(17)),
+            Some(PgValue::Bytea(b"not-hex".to_vec()))
+        );
+    }
+}
@@ -1,44 +1,246 @@
+//! OID -> `PyObject` conversion for decoded tuple columns, gated behind the
+//! `python` feature. For a pure-Rust equivalent, see `pgvalue::decode_typed_value`.
+#![cfg(feature = "python")]
+
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
+use std::collections::HashMap;
 use std::str;
+use std::sync::Arc;
 
-/// Convert PostgreSQL binary/text data to Python objects based on type OID
-pub fn convert_pg_value(py: Python, data: Option<&[u8]>, type_id: u32) -> PyResult<PyObject> {
-    match data {
-        None => Ok(py.None()),
-        Some(bytes) => {
-            // Convert based on PostgreSQL type OID
-            // https://github.com/postgres/postgres/blob/master/src/include/catalog/pg_type.dat
-            match type_id {
-                16 => convert_bool(py, bytes),          // bool
-                20 => convert_int8(py, bytes),          // int8
-                21 => convert_int2(py, bytes),          // int2
-                23 => convert_int4(py, bytes),          // int4
-                25 => convert_text(py, bytes),          // text
-                700 => convert_float4(py, bytes),       // float4
-                701 => convert_float8(py, bytes),       // float8
-                1042 => convert_text(py, bytes),        // char
-                1043 => convert_text(py, bytes),        // varchar
-                1082 => convert_date(py, bytes),        // date
-                1083 => convert_time(py, bytes),        // time
-                1114 => convert_timestamp(py, bytes),   // timestamp
-                1184 => convert_timestamptz(py, bytes), // timestamptz
-                1700 => convert_numeric(py, bytes),     // numeric
-                2950 => convert_uuid(py, bytes),        // uuid
-                114 => convert_json(py, bytes),         // json
-                3802 => convert_json(py, bytes),        // jsonb
-                17 => convert_bytea(py, bytes),         // bytea
-
-                // Array types (OID + 1000 typically)
-                1000..=1999 => convert_array(py, bytes, type_id),
-
-                // Default: treat as text
-                _ => convert_text(py, bytes),
-            }
+use super::messages::TupleValue;
+
+/// Placeholder written for a TOASTed column whose value was unchanged by the
+/// statement and so wasn't sent by the server, matching Debezium's convention
+/// for distinguishing this case from an actual `NULL`.
+pub const UNCHANGED_TOAST_PLACEHOLDER: &str = "__debezium_unavailable_value";
+
+/// PostgreSQL column types recognized by the [`OidTypeRegistry`].
+#[derive(Clone)]
+pub enum PgType {
+    Bool,
+    Int2,
+    Int4,
+    Int8,
+    Float4,
+    Float8,
+    Numeric,
+    Text,
+    Json,
+    Timestamp,
+    Timestamptz,
+    Date,
+    Time,
+    Uuid,
+    Bytea,
+    Array,
+    /// Hook for extension types not covered by the built-ins above.
+    Custom(Arc<dyn Fn(Python, &[u8], i32) -> PyResult<PyObject> + Send + Sync>),
+}
+
+/// Maps PostgreSQL type OIDs to conversion logic for pgoutput's text-format
+/// column bytes (see https://github.com/postgres/postgres/blob/master/src/include/catalog/pg_type.dat).
+///
+/// Built-in OIDs cover the common scalar, JSON, and timestamp types. Unknown
+/// OIDs in the 1000-1999 array range are decoded as arrays; anything else
+/// unknown falls back to a raw-string passthrough. Use `register`/
+/// `register_custom` to add or override handling for extension types.
+pub struct OidTypeRegistry {
+    types: HashMap<u32, PgType>,
+}
+
+impl OidTypeRegistry {
+    pub fn new() -> Self {
+        let mut types = HashMap::new();
+        types.insert(16, PgType::Bool); // bool
+        types.insert(20, PgType::Int8); // int8
+        types.insert(21, PgType::Int2); // int2
+        types.insert(23, PgType::Int4); // int4
+        types.insert(700, PgType::Float4); // float4
+        types.insert(701, PgType::Float8); // float8
+        types.insert(1700, PgType::Numeric); // numeric
+        types.insert(25, PgType::Text); // text
+        types.insert(1042, PgType::Text); // char
+        types.insert(1043, PgType::Text); // varchar
+        types.insert(114, PgType::Json); // json
+        types.insert(3802, PgType::Json); // jsonb
+        types.insert(1114, PgType::Timestamp); // timestamp
+        types.insert(1184, PgType::Timestamptz); // timestamptz
+        types.insert(1082, PgType::Date); // date
+        types.insert(1083, PgType::Time); // time
+        types.insert(2950, PgType::Uuid); // uuid
+        types.insert(17, PgType::Bytea); // bytea
+        Self { types }
+    }
+
+    /// Register (or override) the conversion applied to `type_id`.
+    pub fn register(&mut self, type_id: u32, pg_type: PgType) {
+        self.types.insert(type_id, pg_type);
+    }
+
+    /// Register a custom handler for `type_id`, for extension types not
+    /// covered by the well-known OIDs above. `handler` receives the column's
+    /// raw bytes (text format, or binary format for a `TupleValue::Binary`
+    /// column) and the column's `type_modifier`.
+    pub fn register_custom<F>(&mut self, type_id: u32, handler: F)
+    where
+        F: Fn(Python, &[u8], i32) -> PyResult<PyObject> + Send + Sync + 'static,
+    {
+        self.register(type_id, PgType::Custom(Arc::new(handler)));
+    }
+
+    /// Convert a decoded tuple column to a Python object using the handler
+    /// registered for `type_id`, consulting `type_modifier` where relevant
+    /// (e.g. numeric scale). Unregistered OIDs in the array range fall back to
+    /// array decoding; anything else unregistered falls back to raw text.
+    ///
+    /// `TupleValue::Null` converts to `None`; `TupleValue::UnchangedToast`
+    /// converts to [`UNCHANGED_TOAST_PLACEHOLDER`] rather than `None`, since it
+    /// isn't an actual NULL. `TupleValue::Binary` is decoded using
+    /// PostgreSQL's binary wire format rather than the text format.
+    pub fn convert(
+        &self,
+        py: Python,
+        value: &TupleValue,
+        type_id: u32,
+        type_modifier: i32,
+    ) -> PyResult<PyObject> {
+        match value {
+            TupleValue::Null => Ok(py.None()),
+            TupleValue::UnchangedToast => Ok(UNCHANGED_TOAST_PLACEHOLDER.into_py(py)),
+            TupleValue::Text(bytes) => match self.types.get(&type_id) {
+                Some(pg_type) => self.convert_typed(py, bytes, pg_type, type_modifier),
+                None if (1000..=1999).contains(&type_id) => convert_array(py, bytes, type_id),
+                None => convert_text(py, bytes),
+            },
+            TupleValue::Binary(bytes) => match self.types.get(&type_id) {
+                Some(pg_type) => convert_binary_typed(py, bytes, pg_type, type_modifier),
+                None => convert_bytea_raw(py, bytes),
+            },
+        }
+    }
+
+    fn convert_typed(
+        &self,
+        py: Python,
+        bytes: &[u8],
+        pg_type: &PgType,
+        type_modifier: i32,
+    ) -> PyResult<PyObject> {
+        match pg_type {
+            PgType::Bool => convert_bool(py, bytes),
+            PgType::Int2 => convert_int2(py, bytes),
+            PgType::Int4 => convert_int4(py, bytes),
+            PgType::Int8 => convert_int8(py, bytes),
+            PgType::Float4 => convert_float4(py, bytes),
+            PgType::Float8 => convert_float8(py, bytes),
+            PgType::Numeric => convert_numeric(py, bytes, type_modifier),
+            PgType::Text => convert_text(py, bytes),
+            PgType::Json => convert_json(py, bytes),
+            PgType::Timestamp => convert_timestamp_epoch(py, bytes, false),
+            PgType::Timestamptz => convert_timestamp_epoch(py, bytes, true),
+            PgType::Date => convert_date(py, bytes),
+            PgType::Time => convert_time(py, bytes),
+            PgType::Uuid => convert_uuid(py, bytes),
+            PgType::Bytea => convert_bytea(py, bytes),
+            PgType::Array => convert_array(py, bytes, 0),
+            PgType::Custom(handler) => handler(py, bytes, type_modifier),
         }
     }
 }
 
+impl Default for OidTypeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decode a `TupleValue::Binary` column using PostgreSQL's binary wire format
+/// for `pg_type`. Fixed-width numeric types and UTF-8-compatible text/json/
+/// uuid columns are decoded directly; types whose binary layout needs more
+/// than big-endian reinterpretation (numeric, date/time, arrays) fall back to
+/// the raw bytes, since binary tuples are only sent when the publication
+/// opts in with `binary = true` and most consumers care about the scalar
+/// types above.
+fn convert_binary_typed(
+    py: Python,
+    bytes: &[u8],
+    pg_type: &PgType,
+    type_modifier: i32,
+) -> PyResult<PyObject> {
+    match pg_type {
+        PgType::Bool => Ok((bytes.first().copied().unwrap_or(0) != 0).into_py(py)),
+        PgType::Int2 => Ok(bytes_to_be::<2>(bytes)
+            .map(i16::from_be_bytes)
+            .unwrap_or(0)
+            .into_py(py)),
+        PgType::Int4 => Ok(bytes_to_be::<4>(bytes)
+            .map(i32::from_be_bytes)
+            .unwrap_or(0)
+            .into_py(py)),
+        PgType::Int8 => Ok(bytes_to_be::<8>(bytes)
+            .map(i64::from_be_bytes)
+            .unwrap_or(0)
+            .into_py(py)),
+        PgType::Float4 => Ok(bytes_to_be::<4>(bytes)
+            .map(f32::from_be_bytes)
+            .unwrap_or(0.0)
+            .into_py(py)),
+        PgType::Float8 => Ok(bytes_to_be::<8>(bytes)
+            .map(f64::from_be_bytes)
+            .unwrap_or(0.0)
+            .into_py(py)),
+        PgType::Text => Ok(String::from_utf8_lossy(bytes).into_owned().into_py(py)),
+        // jsonb's binary format has a leading version byte (always 1 so far);
+        // plain json has no prefix and never starts with that byte as text.
+        PgType::Json => convert_json(py, bytes.strip_prefix(&[1u8]).unwrap_or(bytes)),
+        PgType::Uuid => Ok(format_uuid_bytes(bytes).into_py(py)),
+        PgType::Bytea => convert_bytea_raw(py, bytes),
+        PgType::Custom(handler) => handler(py, bytes, type_modifier),
+        PgType::Numeric
+        | PgType::Timestamp
+        | PgType::Timestamptz
+        | PgType::Date
+        | PgType::Time
+        | PgType::Array => convert_bytea_raw(py, bytes),
+    }
+}
+
+fn bytes_to_be<const N: usize>(bytes: &[u8]) -> Option<[u8; N]> {
+    bytes.try_into().ok()
+}
+
+/// Fallback for binary-format columns whose wire layout isn't decoded above:
+/// expose the raw bytes as a Python `bytes` object rather than guessing.
+fn convert_bytea_raw(py: Python, bytes: &[u8]) -> PyResult<PyObject> {
+    Ok(bytes.into_py(py))
+}
+
+fn format_uuid_bytes(bytes: &[u8]) -> String {
+    if bytes.len() != 16 {
+        return hex::encode(bytes);
+    }
+    format!(
+        "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+        bytes[0],
+        bytes[1],
+        bytes[2],
+        bytes[3],
+        bytes[4],
+        bytes[5],
+        bytes[6],
+        bytes[7],
+        bytes[8],
+        bytes[9],
+        bytes[10],
+        bytes[11],
+        bytes[12],
+        bytes[13],
+        bytes[14],
+        bytes[15],
+    )
+}
+
 fn convert_bool(py: Python, data: &[u8]) -> PyResult<PyObject> {
     let s = str::from_utf8(data).unwrap_or("f");
     Ok((s == "t" || s == "true" || s == "1").into_py(py))
@@ -74,10 +276,27 @@ fn convert_text(py: Python, data: &[u8]) -> PyResult<PyObject> {
     Ok(s.into_py(py))
 }
 
-fn convert_numeric(py: Python, data: &[u8]) -> PyResult<PyObject> {
-    // Numeric types sent as text in logical replication
+/// Numeric types are sent as text in logical replication. When the column's
+/// `type_modifier` encodes a declared scale (`typmod != -1`), format the
+/// value as a fixed-scale decimal string so precision survives JSON/float
+/// round-tripping; otherwise fall back to a best-effort float.
+fn convert_numeric(py: Python, data: &[u8], type_modifier: i32) -> PyResult<PyObject> {
     let s = str::from_utf8(data).unwrap_or("0");
-    // Try to parse as float for Python
+
+    if type_modifier > 0 {
+        let scale = ((type_modifier - 4) & 0xFFFF) as usize;
+        // PostgreSQL already prints a scaled numeric with exactly `scale`
+        // fractional digits; keep that text as-is, since re-formatting it
+        // through f64 would drop digits past ~15 significant figures.
+        let frac_len = s.split_once('.').map_or(0, |(_, frac)| frac.len());
+        if frac_len == scale && s.parse::<f64>().is_ok() {
+            return Ok(s.into_py(py));
+        }
+        if let Ok(f) = s.parse::<f64>() {
+            return Ok(format!("{:.*}", scale, f).into_py(py));
+        }
+    }
+
     match s.parse::<f64>() {
         Ok(f) => Ok(f.into_py(py)),
         Err(_) => Ok(s.into_py(py)), // Return as string if can't parse
@@ -94,14 +313,104 @@ fn convert_time(py: Python, data: &[u8]) -> PyResult<PyObject> {
     Ok(s.into_py(py))
 }
 
-fn convert_timestamp(py: Python, data: &[u8]) -> PyResult<PyObject> {
+/// Parse a Postgres text-format timestamp/timestamptz into epoch microseconds,
+/// matching Debezium's `MicroTimestamp`/`ZonedTimestamp` convention. Falls
+/// back to the raw string if the value doesn't match the expected format.
+fn convert_timestamp_epoch(py: Python, data: &[u8], has_tz: bool) -> PyResult<PyObject> {
     let s = str::from_utf8(data).unwrap_or("");
-    Ok(s.into_py(py))
+    match epoch_micros_from_pg_timestamp(s, has_tz) {
+        Some(micros) => Ok(micros.into_py(py)),
+        None => Ok(s.into_py(py)),
+    }
 }
 
-fn convert_timestamptz(py: Python, data: &[u8]) -> PyResult<PyObject> {
-    let s = str::from_utf8(data).unwrap_or("");
-    Ok(s.into_py(py))
+fn epoch_micros_from_pg_timestamp(s: &str, has_tz: bool) -> Option<i64> {
+    let (main, tz) = if has_tz {
+        split_tz_suffix(s)
+    } else {
+        (s, None)
+    };
+
+    let (date_str, time_str) = main.split_once(' ')?;
+
+    let mut date_parts = date_str.splitn(3, '-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: u32 = date_parts.next()?.parse().ok()?;
+    let day: u32 = date_parts.next()?.parse().ok()?;
+
+    let (time_main, frac) = match time_str.split_once('.') {
+        Some((t, f)) => (t, Some(f)),
+        None => (time_str, None),
+    };
+    let mut time_parts = time_main.splitn(3, ':');
+    let hour: i64 = time_parts.next()?.parse().ok()?;
+    let minute: i64 = time_parts.next()?.parse().ok()?;
+    let second: i64 = time_parts.next()?.parse().ok()?;
+
+    let micros: i64 = match frac {
+        Some(f) => {
+            let mut digits = f.to_string();
+            digits.truncate(6);
+            while digits.len() < 6 {
+                digits.push('0');
+            }
+            digits.parse().ok()?
+        }
+        None => 0,
+    };
+
+    let tz_offset_secs = match tz {
+        Some(tz) => parse_tz_offset_secs(tz)?,
+        None => 0,
+    };
+
+    let days = days_from_civil(year, month, day);
+    let seconds_of_day = hour * 3600 + minute * 60 + second;
+    let total_seconds = days * 86_400 + seconds_of_day - tz_offset_secs;
+    Some(total_seconds * 1_000_000 + micros)
+}
+
+/// Split a timestamptz string into its datetime portion and trailing UTC
+/// offset (e.g. `"+00"`, `"-05:30"`), searching only after the date/time
+/// separator so a `-` in the date itself isn't mistaken for the offset sign.
+fn split_tz_suffix(s: &str) -> (&str, Option<&str>) {
+    if let Some(space_idx) = s.find(' ') {
+        let time_and_tz = &s[space_idx..];
+        if let Some(tz_idx) = time_and_tz.rfind(['+', '-']) {
+            let abs_idx = space_idx + tz_idx;
+            return (&s[..abs_idx], Some(&s[abs_idx..]));
+        }
+    }
+    (s, None)
+}
+
+fn parse_tz_offset_secs(tz: &str) -> Option<i64> {
+    if let Some(rest) = tz.strip_prefix('-') {
+        return parse_tz_offset_magnitude(rest).map(|secs| -secs);
+    }
+    parse_tz_offset_magnitude(tz.strip_prefix('+').unwrap_or(tz))
+}
+
+fn parse_tz_offset_magnitude(tz: &str) -> Option<i64> {
+    let mut parts = tz.splitn(2, ':');
+    let hours: i64 = parts.next()?.parse().ok()?;
+    let minutes: i64 = match parts.next() {
+        Some(m) => m.parse().ok()?,
+        None => 0,
+    };
+    Some(hours * 3600 + minutes * 60)
+}
+
+/// Days since the Unix epoch for a proleptic Gregorian civil date
+/// (Howard Hinnant's `days_from_civil` algorithm).
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y / 400 } else { (y - 399) / 400 };
+    let yoe = y - era * 400; // [0, 399]
+    let mp = (if m > 2 { m - 3 } else { m + 9 }) as i64; // [0, 11]
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1; // [0, 365]
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy; // [0, 146096]
+    era * 146_097 + doe - 719_468
 }
 
 fn convert_uuid(py: Python, data: &[u8]) -> PyResult<PyObject> {
@@ -168,7 +477,7 @@ fn convert_array(py: Python, data: &[u8], _type_id: u32) -> PyResult<PyObject> {
 }
 
 /// Convert serde_json::Value to Python object
-fn json_to_py(py: Python, value: &serde_json::Value) -> PyResult<PyObject> {
+pub(crate) fn json_to_py(py: Python, value: &serde_json::Value) -> PyResult<PyObject> {
     match value {
         serde_json::Value::Null => Ok(py.None()),
         serde_json::Value::Bool(b) => Ok(b.into_py(py)),
@@ -1,14 +1,18 @@
+use futures::StreamExt;
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyAny};
-use tokio_postgres::NoTls;
+use pyo3::types::{PyAny, PyDict};
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use futures::StreamExt;
+use tokio_postgres::NoTls;
 
-use crate::pgoutput::{PgOutputDecoder, PgOutputMessage, ReplicationMessage};
-use crate::utils::{build_connection_string, ExponentialBackoff, to_py_err};
+use crate::error::ReplicationError;
+use crate::metrics::Metrics;
+use crate::pgoutput::{PgOutputDecoder, PgOutputMessage, ReplicationMessage, StreamCommitMessage};
+use crate::utils::{build_connection_string, build_tls_config, to_py_err, ExponentialBackoff};
 
-use pgwire_replication::{ReplicationClient, ReplicationConfig as PgReplicationConfig, ReplicationEvent, Lsn, TlsConfig};
+use pgwire_replication::{
+    Lsn, ReplicationClient, ReplicationConfig as PgReplicationConfig, ReplicationEvent,
+};
 
 /// PostgreSQL logical replication reader
 #[pyclass]
@@ -22,12 +26,23 @@ struct ReplicationConfig {
     publication_name: String,
     slot_name: String,
     host: String,
+    hostaddr: Option<String>,
     database: String,
     port: u16,
     user: String,
     password: String,
     start_lsn: Option<String>,
     auto_acknowledge: bool,
+    sslmode: String,
+    ssl_root_cert: Option<String>,
+    ssl_cert: Option<String>,
+    ssl_key: Option<String>,
+    allow_invalid_certs: bool,
+    max_reconnect_attempts: Option<u32>,
+    metrics: Arc<Metrics>,
+    snapshot_mode: String,
+    emit_transaction_metadata: bool,
+    streaming: bool,
 }
 
 struct ReaderState {
@@ -36,12 +51,31 @@ struct ReaderState {
     stopped: bool,
     current_lsn: Option<u64>,
     pending_lsn: Option<Lsn>,
+    backoff: ExponentialBackoff,
+    reconnect_attempts: u32,
+    snapshot_done: bool,
+    snapshot_queue: std::collections::VecDeque<ReplicationMessage>,
+    /// Rows emitted since the current transaction's `BeginMessage`, reset to
+    /// zero there and read (then reset) by the matching `CommitMessage` so
+    /// the commit event can report how many changes it covered.
+    tx_event_count: u32,
+    /// Rows (with the LSN they arrived at) from an in-progress *streamed*
+    /// transaction, keyed by xid. Held here instead of being emitted
+    /// immediately, since a streamed transaction isn't known to be durable
+    /// until its `StreamCommitMessage` arrives — a `StreamAbortMessage`
+    /// discards the buffered entry for that xid instead.
+    stream_buffers: std::collections::HashMap<u32, Vec<(u64, PgOutputMessage)>>,
+    /// Converted messages ready to return, produced when a `StreamCommit`
+    /// flushes more than one buffered row at once (`next_message` can only
+    /// hand back one message per call).
+    ready_queue: std::collections::VecDeque<ReplicationMessage>,
 }
 
 #[pymethods]
 impl LogicalReplicationReader {
     #[new]
-    #[pyo3(signature = (publication_name, slot_name, host, database, port=5432, user="postgres", password="", start_lsn=None, auto_acknowledge=true))]
+    #[pyo3(signature = (publication_name, slot_name, host, database, port=5432, user="postgres", password="", hostaddr=None, start_lsn=None, auto_acknowledge=true, sslmode="disable", ssl_root_cert=None, ssl_cert=None, ssl_key=None, allow_invalid_certs=false, max_reconnect_attempts=None, snapshot_mode="never", emit_transaction_metadata=false, streaming=false))]
+    #[allow(clippy::too_many_arguments)]
     fn new(
         publication_name: String,
         slot_name: String,
@@ -50,229 +84,529 @@ impl LogicalReplicationReader {
         port: u16,
         user: &str,
         password: &str,
+        hostaddr: Option<String>,
         start_lsn: Option<String>,
         auto_acknowledge: bool,
+        sslmode: &str,
+        ssl_root_cert: Option<String>,
+        ssl_cert: Option<String>,
+        ssl_key: Option<String>,
+        allow_invalid_certs: bool,
+        max_reconnect_attempts: Option<u32>,
+        snapshot_mode: &str,
+        emit_transaction_metadata: bool,
+        streaming: bool,
     ) -> Self {
         let config = ReplicationConfig {
             publication_name,
             slot_name,
             host,
+            hostaddr,
             database,
             port,
             user: user.to_string(),
             password: password.to_string(),
             start_lsn,
             auto_acknowledge,
+            sslmode: sslmode.to_string(),
+            ssl_root_cert,
+            ssl_cert,
+            ssl_key,
+            allow_invalid_certs,
+            max_reconnect_attempts,
+            metrics: Arc::new(Metrics::new()),
+            snapshot_mode: snapshot_mode.to_string(),
+            emit_transaction_metadata,
+            streaming,
         };
-        
+
         let state = ReaderState {
             decoder: PgOutputDecoder::new(),
             client: None,
             stopped: false,
             current_lsn: None,
             pending_lsn: None,
+            backoff: ExponentialBackoff::new(),
+            reconnect_attempts: 0,
+            snapshot_done: config.snapshot_mode == "never",
+            snapshot_queue: std::collections::VecDeque::new(),
+            tx_event_count: 0,
+            stream_buffers: std::collections::HashMap::new(),
+            ready_queue: std::collections::VecDeque::new(),
         };
-        
+
         Self {
             config,
             state: Arc::new(Mutex::new(state)),
         }
     }
-    
+
     fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
         slf
     }
-    
+
     fn __anext__<'a>(&'a self, py: Python<'a>) -> PyResult<Option<&'a PyAny>> {
         let state = self.state.clone();
         let config = self.config.clone();
-        
+
         Ok(Some(pyo3_asyncio::tokio::future_into_py(py, async move {
-            // Initialize connection if needed
-            {
-                let mut state_guard = state.lock().await;
-                if state_guard.client.is_none() && !state_guard.stopped {
-                    // Create replication client
-                    let start_lsn = if let Some(lsn_str) = &config.start_lsn {
-                        Lsn::parse(lsn_str).unwrap_or(Lsn::ZERO)
-                    } else {
-                        Lsn::ZERO
-                    };
-                    
-                    let repl_config = PgReplicationConfig {
-                        host: config.host.clone(),  
-                        port: config.port,
-                        user: config.user.clone(),
-                        password: config.password.clone(),
-                        database: config.database.clone(),
-                        slot: config.slot_name.clone(),
-                        publication: config.publication_name.clone(),
-                        start_lsn,
-                        stop_at_lsn: None,
-                        tls: TlsConfig::disabled(),
-                        status_interval: std::time::Duration::from_secs(10),
-                        idle_wakeup_interval: std::time::Duration::from_secs(10),
-                        buffer_events: 8192,
-                    };
-                    
-                    let client = ReplicationClient::connect(repl_config)
-                        .await
-                        .map_err(|e| to_py_err(format!("Connection failed: {}", e)))?;
-                    
-                    state_guard.client = Some(client);
-                }
-            }
-            
-            // Get next event and convert to message
+            Self::next_message(state, config).await
+        })?))
+    }
+
+    /// Drain the replication stream directly into an NDJSON file at `path`.
+    ///
+    /// Stops after `max_messages` records, or when the stream ends / `stop()` is
+    /// called if `max_messages` is `None`. Returns the number of messages written.
+    #[pyo3(signature = (path, max_messages=None))]
+    fn drain_ndjson<'a>(
+        &'a self,
+        py: Python<'a>,
+        path: String,
+        max_messages: Option<usize>,
+    ) -> PyResult<&'a PyAny> {
+        let state = self.state.clone();
+        let config = self.config.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let file = std::fs::File::create(&path)
+                .map_err(|e| to_py_err(format!("Failed to open {}: {}", path, e)))?;
+            let mut writer = crate::pgoutput::DebeziumNdjsonWriter::new(file);
+            let mut written = 0usize;
+
             loop {
-                // Take client temporarily out of state to call recv() without holding lock
-                let mut client_opt = {
-                    let mut state_guard = state.lock().await;
-                    if state_guard.stopped {
-                        return Ok(None);
+                if max_messages.is_some_and(|max| written >= max) {
+                    break;
+                }
+
+                match Self::next_message(state.clone(), config.clone()).await? {
+                    Some(message) => {
+                        Python::with_gil(|py| writer.write_message(py, &message))?;
+                        written += 1;
                     }
-                    state_guard.client.take()
-                };
-                
-                let mut client = match client_opt {
-                    Some(c) => c,
-                    None => return Ok(None),
-                };
-                
-                // Call recv() without holding the lock
-                let event_result = client.recv().await;
-                
-                // Now process the event with access to state
-                match event_result {
-                    Ok(Some(event)) => {
-                        match event {
-                            ReplicationEvent::XLogData { data, wal_end, .. } => {
-                                // Get decoder and decode
-                                let (decoded_msg, should_return_msg) = {
-                                    let mut state_guard = state.lock().await;
-                                    
-                                    match state_guard.decoder.decode(data) {
-                                        Ok(pg_msg) => {
-                                            // Update LSN based on auto_acknowledge setting
-                                            if config.auto_acknowledge {
-                                                client.update_applied_lsn(wal_end);
-                                            } else {
-                                                // Store pending LSN for manual acknowledgment
-                                                state_guard.pending_lsn = Some(wal_end);
-                                            }
-                                            
-                                            // Convert to ReplicationMessage
-                                            let lsn_u64 = wal_end.into();
-                                            let repl_msg = Python::with_gil(|py| {
-                                                Self::convert_message(py, &pg_msg, &state_guard.decoder, lsn_u64, &config)
-                                            });
-                                            
-                                            // Put client back
-                                            state_guard.client = Some(client);
-                                            
-                                            (Ok(()), repl_msg)
+                    None => break,
+                }
+            }
+
+            Ok(written)
+        })
+    }
+}
+
+impl LogicalReplicationReader {
+    async fn next_message(
+        state: Arc<Mutex<ReaderState>>,
+        config: ReplicationConfig,
+    ) -> PyResult<Option<ReplicationMessage>> {
+        // Serve the initial snapshot (if requested) before establishing the
+        // replication connection: the snapshot may need to create the
+        // replication slot itself (to capture its EXPORT_SNAPSHOT id), and
+        // that must happen before `connect_with_backoff` starts streaming
+        // from the slot, or the slot (and its snapshot/LSN pairing) would
+        // already exist by the time the snapshot runs.
+        let should_snapshot = {
+            let state_guard = state.lock().await;
+            !state_guard.snapshot_done && !state_guard.stopped
+        };
+        if should_snapshot {
+            Self::run_snapshot(&state, &config).await?;
+            let mut state_guard = state.lock().await;
+            state_guard.snapshot_done = true;
+        }
+
+        // Initialize connection if needed
+        let needs_connect = {
+            let state_guard = state.lock().await;
+            state_guard.client.is_none() && !state_guard.stopped
+        };
+        if needs_connect {
+            let client = Self::connect_with_backoff(&state, &config).await?;
+            let mut state_guard = state.lock().await;
+            state_guard.client = Some(client);
+        }
+
+        {
+            let mut state_guard = state.lock().await;
+            if let Some(msg) = state_guard.snapshot_queue.pop_front() {
+                return Ok(Some(msg));
+            }
+            if config.snapshot_mode == "initial_only" {
+                state_guard.stopped = true;
+                return Ok(None);
+            }
+        }
+
+        // Get next event and convert to message
+        loop {
+            // Take client temporarily out of state to call recv() without holding lock
+            let mut client_opt = {
+                let mut state_guard = state.lock().await;
+                if state_guard.stopped {
+                    return Ok(None);
+                }
+                if let Some(msg) = state_guard.ready_queue.pop_front() {
+                    config.metrics.record_message(&msg.op, 0);
+                    return Ok(Some(msg));
+                }
+                state_guard.client.take()
+            };
+
+            let mut client = match client_opt {
+                Some(c) => c,
+                None => return Ok(None),
+            };
+
+            // Call recv() without holding the lock
+            let event_result = client.recv().await;
+
+            // Now process the event with access to state
+            match event_result {
+                Ok(Some(event)) => {
+                    match event {
+                        ReplicationEvent::XLogData { data, wal_end, .. } => {
+                            // Get decoder and decode
+                            let data_len = data.len();
+                            let (decoded_msg, should_return_msg) = {
+                                let mut state_guard = state.lock().await;
+
+                                match state_guard.decoder.decode(data) {
+                                    Ok(pg_msg) => {
+                                        // Update LSN based on auto_acknowledge setting
+                                        if config.auto_acknowledge {
+                                            client.update_applied_lsn(wal_end);
+                                            config.metrics.set_last_ack_lsn(wal_end.into());
+                                        } else {
+                                            // Store pending LSN for manual acknowledgment
+                                            state_guard.pending_lsn = Some(wal_end);
                                         }
-                                        Err(e) => {
-                                            eprintln!("Failed to decode pgoutput message: {}", e);
-                                            // Put client back
-                                            state_guard.client = Some(client);
-                                            (Err(()), None)
+
+                                        // Convert to ReplicationMessage
+                                        let lsn_u64 = wal_end.into();
+                                        state_guard.current_lsn = Some(lsn_u64);
+                                        config.metrics.set_current_lsn(lsn_u64);
+
+                                        let repl_msg = if let Some(xid) = streamed_xid(&pg_msg) {
+                                            // Row from an in-progress streamed transaction:
+                                            // hold it until the matching StreamCommit/StreamAbort.
+                                            state_guard
+                                                .stream_buffers
+                                                .entry(xid)
+                                                .or_default()
+                                                .push((lsn_u64, pg_msg));
+                                            None
+                                        } else {
+                                            match &pg_msg {
+                                                PgOutputMessage::StreamCommit(stream_commit) => {
+                                                    let buffered = state_guard
+                                                        .stream_buffers
+                                                        .remove(&stream_commit.xid)
+                                                        .unwrap_or_default();
+                                                    let mut flushed =
+                                                        std::collections::VecDeque::new();
+                                                    for (buffered_lsn, buffered_msg) in buffered {
+                                                        let converted = Python::with_gil(|py| {
+                                                            let reader_state = &mut *state_guard;
+                                                            Self::convert_message(
+                                                                py,
+                                                                &buffered_msg,
+                                                                &reader_state.decoder,
+                                                                buffered_lsn,
+                                                                &config,
+                                                                &mut reader_state.tx_event_count,
+                                                            )
+                                                        });
+                                                        if let Some(m) = converted {
+                                                            flushed.push_back(m);
+                                                        }
+                                                    }
+                                                    let event_count = flushed.len() as u32;
+                                                    if let Some(commit_event) =
+                                                        Python::with_gil(|py| {
+                                                            Self::convert_stream_commit(
+                                                                py,
+                                                                stream_commit,
+                                                                lsn_u64,
+                                                                &config,
+                                                                event_count,
+                                                            )
+                                                        })
+                                                    {
+                                                        flushed.push_back(commit_event);
+                                                    }
+                                                    let first = flushed.pop_front();
+                                                    state_guard.ready_queue.extend(flushed);
+                                                    first
+                                                }
+                                                PgOutputMessage::StreamAbort(stream_abort) => {
+                                                    // Transaction was rolled back: discard the
+                                                    // buffered rows instead of emitting them. No
+                                                    // boundary event is emitted either — unlike
+                                                    // StreamCommit, there's nothing for a consumer
+                                                    // to observe about a transaction that never
+                                                    // happened.
+                                                    state_guard
+                                                        .stream_buffers
+                                                        .remove(&stream_abort.xid);
+                                                    None
+                                                }
+                                                _ => Python::with_gil(|py| {
+                                                    let reader_state = &mut *state_guard;
+                                                    Self::convert_message(
+                                                        py,
+                                                        &pg_msg,
+                                                        &reader_state.decoder,
+                                                        lsn_u64,
+                                                        &config,
+                                                        &mut reader_state.tx_event_count,
+                                                    )
+                                                }),
+                                            }
+                                        };
+                                        if let Some(msg) = &repl_msg {
+                                            config.metrics.record_message(&msg.op, data_len);
                                         }
+
+                                        // Put client back
+                                        state_guard.client = Some(client);
+
+                                        (Ok(()), repl_msg)
                                     }
-                                };
-                                
-                                if let Ok(()) = decoded_msg {
-                                    if let Some(msg) = should_return_msg {
-                                        return Ok(Some(msg));
+                                    Err(e) => {
+                                        let repl_err = ReplicationError::from(e);
+                                        eprintln!(
+                                            "Failed to decode pgoutput message: {}",
+                                            repl_err
+                                        );
+                                        config.metrics.record_decode_failure();
+                                        // Put client back
+                                        state_guard.client = Some(client);
+                                        (Err(()), None)
                                     }
                                 }
-                                // Continue loop for other events
-                            }
-                            _ => {
-                                // For other events, just put client back and continue
-                                let mut state_guard = state.lock().await;
-                                state_guard.client = Some(client);
-                                
-                                if matches!(event, ReplicationEvent::StoppedAt { .. }) {
-                                    return Ok(None);
+                            };
+
+                            if let Ok(()) = decoded_msg {
+                                if let Some(msg) = should_return_msg {
+                                    return Ok(Some(msg));
                                 }
-                                // Continue loop
                             }
+                            // Continue loop for other events
+                        }
+                        _ => {
+                            // For other events, just put client back and continue
+                            let mut state_guard = state.lock().await;
+                            state_guard.client = Some(client);
+
+                            if matches!(event, ReplicationEvent::StoppedAt { .. }) {
+                                return Ok(None);
+                            }
+                            // Continue loop
                         }
                     }
-                    Ok(None) => {
-                        // Put client back
-                        let mut state_guard = state.lock().await;
-                        state_guard.client = Some(client);
-                        return Ok(None);
+                }
+                Ok(None) => {
+                    // Put client back
+                    let mut state_guard = state.lock().await;
+                    state_guard.client = Some(client);
+                    return Ok(None);
+                }
+                Err(e) => {
+                    let repl_err = ReplicationError::from_message(e.to_string());
+                    if !repl_err.is_retriable() {
+                        return Err(to_py_err(format!(
+                            "Non-retriable replication error: {}",
+                            repl_err
+                        )));
                     }
-                    Err(e) => {
-                        // Put client back
-                        let mut state_guard = state.lock().await;
-                        state_guard.client = Some(client);
-                        return Err(to_py_err(format!("Replication error: {}", e)));
+                    // Drop the broken client and reconnect, resuming from the last
+                    // confirmed LSN, instead of surfacing a transient error to Python.
+                    eprintln!("Replication error, reconnecting: {}", repl_err);
+                    config.metrics.record_reconnect();
+                    drop(client);
+                    let new_client = Self::connect_with_backoff(&state, &config).await?;
+                    let mut state_guard = state.lock().await;
+                    state_guard.client = Some(new_client);
+                    // Continue loop
+                }
+            }
+        }
+    }
+
+    /// Connect to the replication slot, retrying with exponential backoff on
+    /// failure. Resumes from the last *acknowledged* LSN (falling back to
+    /// `config.start_lsn`, then `Lsn::ZERO`) so a reconnect never re-reads
+    /// already-acknowledged changes, nor skips changes that were decoded but
+    /// never acknowledged (relevant when `auto_acknowledge=false` and a
+    /// disconnect happens before the caller calls `acknowledge()`). Gives up
+    /// once `config.max_reconnect_attempts` is exceeded, if set.
+    ///
+    /// Note: `config.hostaddr` is *not* used here — `pgwire_replication`'s
+    /// `ReplicationConfig` has no field to supply a pre-resolved address
+    /// alongside `host`, so every attempt of this loop still resolves `host`
+    /// via DNS. `hostaddr` only skips resolution on the plain `tokio_postgres`
+    /// connections `run_snapshot`/`ensure_slot_with_snapshot` open via
+    /// `build_connection_string`.
+    async fn connect_with_backoff(
+        state: &Arc<Mutex<ReaderState>>,
+        config: &ReplicationConfig,
+    ) -> PyResult<ReplicationClient> {
+        loop {
+            let stopped = state.lock().await.stopped;
+            if stopped {
+                return Err(to_py_err(
+                    "Reader stopped before a replication connection could be established",
+                ));
+            }
+
+            let acked_lsn = config.metrics.last_ack_lsn();
+            let start_lsn = if acked_lsn > 0 {
+                Lsn::from(acked_lsn)
+            } else {
+                config
+                    .start_lsn
+                    .as_deref()
+                    .and_then(|s| Lsn::parse(s).ok())
+                    .unwrap_or(Lsn::ZERO)
+            };
+
+            let repl_config = PgReplicationConfig {
+                host: config.host.clone(),
+                port: config.port,
+                user: config.user.clone(),
+                password: config.password.clone(),
+                database: config.database.clone(),
+                slot: config.slot_name.clone(),
+                publication: config.publication_name.clone(),
+                start_lsn,
+                stop_at_lsn: None,
+                tls: build_tls_config(
+                    &config.sslmode,
+                    config.ssl_root_cert.as_deref(),
+                    config.ssl_cert.as_deref(),
+                    config.ssl_key.as_deref(),
+                    config.allow_invalid_certs,
+                )?,
+                status_interval: std::time::Duration::from_secs(10),
+                idle_wakeup_interval: std::time::Duration::from_secs(10),
+                buffer_events: 8192,
+                streaming: config.streaming,
+            };
+
+            match ReplicationClient::connect(repl_config).await {
+                Ok(client) => {
+                    let mut state_guard = state.lock().await;
+                    state_guard.backoff.reset();
+                    state_guard.reconnect_attempts = 0;
+                    return Ok(client);
+                }
+                Err(e) => {
+                    let repl_err = ReplicationError::from_message(e.to_string());
+                    if !repl_err.is_retriable() {
+                        return Err(to_py_err(format!(
+                            "Non-retriable connection error: {}",
+                            repl_err
+                        )));
+                    }
+
+                    let mut state_guard = state.lock().await;
+                    state_guard.reconnect_attempts += 1;
+                    let attempts = state_guard.reconnect_attempts;
+                    if let Some(max) = config.max_reconnect_attempts {
+                        if attempts > max {
+                            return Err(to_py_err(format!(
+                                "Connection failed after {} attempts: {}",
+                                attempts, repl_err
+                            )));
+                        }
                     }
+                    eprintln!(
+                        "Replication connection failed (attempt {}), retrying: {}",
+                        attempts, repl_err
+                    );
+                    state_guard.backoff.wait().await;
                 }
             }
-        })?))
+        }
     }
-    
+}
+
+#[pymethods]
+impl LogicalReplicationReader {
     fn stop<'a>(&'a self, py: Python<'a>) -> PyResult<&'a PyAny> {
         let state = self.state.clone();
-        
+
         pyo3_asyncio::tokio::future_into_py(py, async move {
             let mut state_guard = state.lock().await;
             state_guard.stopped = true;
-            state_guard.client = None;  // Drop the client
-            
+            state_guard.client = None; // Drop the client
+
             Ok(())
         })
     }
-    
+
     /// Manually acknowledge processing up to the specified or pending LSN.
     /// Only needed when auto_acknowledge=False.
     #[pyo3(signature = (lsn=None))]
     fn acknowledge<'py>(&'py self, py: Python<'py>, lsn: Option<String>) -> PyResult<&'py PyAny> {
         let state = self.state.clone();
-        
+        let metrics = self.config.metrics.clone();
+
         pyo3_asyncio::tokio::future_into_py(py, async move {
             let mut state_guard = state.lock().await;
-            
+
             // Take client temporarily
-            let client = state_guard.client.take()
-                .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                    "Client not available - may be in use by read_message()"
-                ))?;
-            
+            let client = state_guard.client.take().ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "Client not available - may be in use by read_message()",
+                )
+            })?;
+
             // Determine which LSN to acknowledge
             let lsn_to_ack = if let Some(lsn_str) = lsn {
                 // Parse provided LSN string
-                let lsn_u64 = lsn_str.parse::<u64>()
-                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
-                        format!("Invalid LSN format: {}", e)
-                    ))?;
+                let lsn_u64 = lsn_str.parse::<u64>().map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Invalid LSN format: {}",
+                        e
+                    ))
+                })?;
                 Lsn::from(lsn_u64)
             } else {
                 // Use pending LSN
-                state_guard.pending_lsn
-                    .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                        "No pending LSN to acknowledge"
-                    ))?
+                state_guard.pending_lsn.ok_or_else(|| {
+                    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                        "No pending LSN to acknowledge",
+                    )
+                })?
             };
-            
+
             // Update applied LSN
             let mut client = client;
             client.update_applied_lsn(lsn_to_ack);
-            
+            metrics.set_last_ack_lsn(lsn_to_ack.into());
+
             // Clear pending LSN
             state_guard.pending_lsn = None;
-            
+
             // Put client back
             state_guard.client = Some(client);
-            
+
             Ok(())
         })
     }
+
+    /// Snapshot replication health and throughput counters as a dict:
+    /// `messages_total`, `inserts_total`, `updates_total`, `deletes_total`,
+    /// `decode_failures_total`, `bytes_processed_total`, `reconnects_total`,
+    /// `current_lsn`, `last_ack_lsn`, `replication_lag_bytes`.
+    fn stats(&self, py: Python) -> PyResult<PyObject> {
+        self.config.metrics.to_py_dict(py)
+    }
+
+    /// Render the same counters as `stats()` in Prometheus text exposition format.
+    fn metrics_text(&self) -> String {
+        self.config.metrics.to_prometheus_text()
+    }
 }
 
 impl LogicalReplicationReader {
@@ -283,58 +617,78 @@ impl LogicalReplicationReader {
         decoder: &PgOutputDecoder,
         lsn: u64,
         config: &ReplicationConfig,
+        tx_event_count: &mut u32,
     ) -> Option<ReplicationMessage> {
         use std::time::{SystemTime, UNIX_EPOCH};
-        
+
         // Get current timestamp
         let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
         let ts_ms = now.as_millis() as i64;
         let ts_us = now.as_micros() as i64;
         let ts_ns = now.as_nanos() as i64;
-        
-        // Helper to create source metadata (Debezium format)
-        fn create_source_metadata(
-            py: Python,
-            lsn: u64,
-            ts_ms: i64,
-            database: &str,
-            schema: &str,
-            table: &str,
-            is_snapshot: bool,
-        ) -> PyObject {
-            let source = PyDict::new(py);
-            source.set_item("version", "0.1.0").ok();
-            source.set_item("connector", "pgoutput-decoder").ok();
-            source.set_item("name", "pgoutput-decoder").ok();
-            source.set_item("ts_ms", ts_ms).ok();
-            source.set_item("snapshot", if is_snapshot { "true" } else { "false" }).ok();
-            source.set_item("db", database).ok();
-            source.set_item("schema", schema).ok();
-            source.set_item("table", table).ok();
-            source.set_item("lsn", lsn).ok();
-            source.into()
-        }
-        
+
         match msg {
-            PgOutputMessage::Begin(_) => None,
-            PgOutputMessage::Commit(_) => None,
+            PgOutputMessage::Begin(begin) => {
+                *tx_event_count = 0;
+                if !config.emit_transaction_metadata {
+                    return None;
+                }
+                let after = PyDict::new(py);
+                after.set_item("id", begin.xid.to_string()).ok()?;
+                after.set_item("final_lsn", begin.final_lsn).ok()?;
+                let source =
+                    create_source_metadata(py, lsn, ts_ms, &config.database, "", "", false);
+                Some(ReplicationMessage {
+                    before: None,
+                    after: Some(after.into()),
+                    source,
+                    op: "begin".to_string(),
+                    ts_ms,
+                    ts_us: Some(ts_us),
+                    ts_ns: Some(ts_ns),
+                })
+            }
+            PgOutputMessage::Commit(commit) => {
+                let event_count = *tx_event_count;
+                *tx_event_count = 0;
+                if !config.emit_transaction_metadata {
+                    return None;
+                }
+                let after = PyDict::new(py);
+                after.set_item("end_lsn", commit.end_lsn).ok()?;
+                after
+                    .set_item("commit_ts_ms", pg_timestamp_to_unix_ms(commit.timestamp))
+                    .ok()?;
+                after.set_item("event_count", event_count).ok()?;
+                let source =
+                    create_source_metadata(py, lsn, ts_ms, &config.database, "", "", false);
+                Some(ReplicationMessage {
+                    before: None,
+                    after: Some(after.into()),
+                    source,
+                    op: "commit".to_string(),
+                    ts_ms,
+                    ts_us: Some(ts_us),
+                    ts_ns: Some(ts_ns),
+                })
+            }
             PgOutputMessage::Relation(_) => None,
-            
+
             PgOutputMessage::Insert(insert) => {
                 if let Some(relation) = decoder.get_relation(insert.rel_id) {
+                    *tx_event_count += 1;
                     // Build "after" data
                     let after_dict = PyDict::new(py);
                     for (i, col_value) in insert.tuple.iter().enumerate() {
                         if let Some(col_info) = relation.columns.get(i) {
-                            let py_value = crate::pgoutput::convert_pg_value(
-                                py,
-                                col_value.as_ref().map(|v| v.as_slice()),
-                                col_info.type_id,
-                            ).ok()?;
+                            let py_value = decoder
+                                .type_registry()
+                                .convert(py, col_value, col_info.type_id, col_info.type_modifier)
+                                .ok()?;
                             after_dict.set_item(&col_info.name, py_value).ok()?;
                         }
                     }
-                    
+
                     let source = create_source_metadata(
                         py,
                         lsn,
@@ -344,7 +698,7 @@ impl LogicalReplicationReader {
                         &relation.name,
                         false,
                     );
-                    
+
                     Some(ReplicationMessage {
                         before: None,
                         after: Some(after_dict.into()),
@@ -358,19 +712,24 @@ impl LogicalReplicationReader {
                     None
                 }
             }
-            
+
             PgOutputMessage::Update(update) => {
                 if let Some(relation) = decoder.get_relation(update.rel_id) {
+                    *tx_event_count += 1;
                     // Build "before" data (if available)
                     let before = if let Some(old_tuple) = &update.old_tuple {
                         let before_dict = PyDict::new(py);
                         for (i, col_value) in old_tuple.iter().enumerate() {
                             if let Some(col_info) = relation.columns.get(i) {
-                                let py_value = crate::pgoutput::convert_pg_value(
-                                    py,
-                                    col_value.as_ref().map(|v| v.as_slice()),
-                                    col_info.type_id,
-                                ).ok()?;
+                                let py_value = decoder
+                                    .type_registry()
+                                    .convert(
+                                        py,
+                                        col_value,
+                                        col_info.type_id,
+                                        col_info.type_modifier,
+                                    )
+                                    .ok()?;
                                 before_dict.set_item(&col_info.name, py_value).ok()?;
                             }
                         }
@@ -378,20 +737,19 @@ impl LogicalReplicationReader {
                     } else {
                         None
                     };
-                    
+
                     // Build "after" data
                     let after_dict = PyDict::new(py);
                     for (i, col_value) in update.new_tuple.iter().enumerate() {
                         if let Some(col_info) = relation.columns.get(i) {
-                            let py_value = crate::pgoutput::convert_pg_value(
-                                py,
-                                col_value.as_ref().map(|v| v.as_slice()),
-                                col_info.type_id,
-                            ).ok()?;
+                            let py_value = decoder
+                                .type_registry()
+                                .convert(py, col_value, col_info.type_id, col_info.type_modifier)
+                                .ok()?;
                             after_dict.set_item(&col_info.name, py_value).ok()?;
                         }
                     }
-                    
+
                     let source = create_source_metadata(
                         py,
                         lsn,
@@ -401,7 +759,7 @@ impl LogicalReplicationReader {
                         &relation.name,
                         false,
                     );
-                    
+
                     Some(ReplicationMessage {
                         before,
                         after: Some(after_dict.into()),
@@ -415,22 +773,22 @@ impl LogicalReplicationReader {
                     None
                 }
             }
-            
+
             PgOutputMessage::Delete(delete) => {
                 if let Some(relation) = decoder.get_relation(delete.rel_id) {
+                    *tx_event_count += 1;
                     // Build "before" data
                     let before_dict = PyDict::new(py);
                     for (i, col_value) in delete.old_tuple.iter().enumerate() {
                         if let Some(col_info) = relation.columns.get(i) {
-                            let py_value = crate::pgoutput::convert_pg_value(
-                                py,
-                                col_value.as_ref().map(|v| v.as_slice()),
-                                col_info.type_id,
-                            ).ok()?;
+                            let py_value = decoder
+                                .type_registry()
+                                .convert(py, col_value, col_info.type_id, col_info.type_modifier)
+                                .ok()?;
                             before_dict.set_item(&col_info.name, py_value).ok()?;
                         }
                     }
-                    
+
                     let source = create_source_metadata(
                         py,
                         lsn,
@@ -440,7 +798,7 @@ impl LogicalReplicationReader {
                         &relation.name,
                         false,
                     );
-                    
+
                     Some(ReplicationMessage {
                         before: Some(before_dict.into()),
                         after: None,
@@ -454,8 +812,268 @@ impl LogicalReplicationReader {
                     None
                 }
             }
-            
+
+            _ => None,
+        }
+    }
+
+    /// Build the commit-equivalent boundary event for a streamed transaction's
+    /// `StreamCommitMessage`, mirroring what `convert_message` synthesizes for
+    /// an ordinary `CommitMessage` (chunk1-5's `end_lsn`/`commit_ts_ms`/
+    /// `event_count` fields). Streamed transactions have no running
+    /// `tx_event_count` — their rows live in `ReaderState.stream_buffers`
+    /// until the commit — so `event_count` is passed in directly as however
+    /// many buffered rows were actually flushed. `StreamAbort` has no
+    /// equivalent: its rows are discarded, not emitted, so there's nothing to
+    /// report a boundary for.
+    fn convert_stream_commit(
+        py: Python,
+        commit: &StreamCommitMessage,
+        lsn: u64,
+        config: &ReplicationConfig,
+        event_count: u32,
+    ) -> Option<ReplicationMessage> {
+        if !config.emit_transaction_metadata {
+            return None;
+        }
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+        let ts_ms = now.as_millis() as i64;
+        let ts_us = now.as_micros() as i64;
+        let ts_ns = now.as_nanos() as i64;
+
+        let after = PyDict::new(py);
+        after.set_item("id", commit.xid.to_string()).ok()?;
+        after.set_item("end_lsn", commit.end_lsn).ok()?;
+        after
+            .set_item("commit_ts_ms", pg_timestamp_to_unix_ms(commit.timestamp))
+            .ok()?;
+        after.set_item("event_count", event_count).ok()?;
+        let source = create_source_metadata(py, lsn, ts_ms, &config.database, "", "", false);
+        Some(ReplicationMessage {
+            before: None,
+            after: Some(after.into()),
+            source,
+            op: "commit".to_string(),
+            ts_ms,
+            ts_us: Some(ts_us),
+            ts_ns: Some(ts_ns),
+        })
+    }
+}
+
+/// The xid a row-change message was sent under, if it arrived as part of an
+/// in-progress streamed transaction (see `StreamStartMessage`) rather than as
+/// part of an already-committed one.
+fn streamed_xid(msg: &PgOutputMessage) -> Option<u32> {
+    match msg {
+        PgOutputMessage::Insert(m) => m.xid,
+        PgOutputMessage::Update(m) => m.xid,
+        PgOutputMessage::Delete(m) => m.xid,
+        PgOutputMessage::Truncate(m) => m.xid,
+        _ => None,
+    }
+}
+
+/// Microseconds between the Unix epoch and the pgoutput wire protocol's
+/// epoch (2000-01-01T00:00:00Z), used to convert `CommitMessage`/`StreamCommitMessage`
+/// timestamps (which are sent as microseconds since the latter) into
+/// Unix-epoch milliseconds.
+const PG_EPOCH_UNIX_MICROS: i64 = 946_684_800_000_000;
+
+fn pg_timestamp_to_unix_ms(pg_micros: i64) -> i64 {
+    (pg_micros + PG_EPOCH_UNIX_MICROS) / 1_000
+}
+
+/// Build Debezium-style source metadata shared by streamed and snapshot messages.
+fn create_source_metadata(
+    py: Python,
+    lsn: u64,
+    ts_ms: i64,
+    database: &str,
+    schema: &str,
+    table: &str,
+    is_snapshot: bool,
+) -> PyObject {
+    let source = PyDict::new(py);
+    source.set_item("version", "0.1.0").ok();
+    source.set_item("connector", "pgoutput-decoder").ok();
+    source.set_item("name", "pgoutput-decoder").ok();
+    source.set_item("ts_ms", ts_ms).ok();
+    source
+        .set_item("snapshot", if is_snapshot { "true" } else { "false" })
+        .ok();
+    source.set_item("db", database).ok();
+    source.set_item("schema", schema).ok();
+    source.set_item("table", table).ok();
+    source.set_item("lsn", lsn).ok();
+    source.into()
+}
+
+impl LogicalReplicationReader {
+    /// If `config.slot_name` doesn't exist yet, create it over a dedicated
+    /// replication-mode connection with `EXPORT_SNAPSHOT`, returning the
+    /// exported snapshot id. `run_snapshot` then `SET TRANSACTION SNAPSHOT`s
+    /// to exactly this point before reading, so the snapshot and the slot's
+    /// starting LSN line up and streaming can pick up immediately afterwards
+    /// with no gap. Returns `None` if the slot already exists (e.g. a prior
+    /// run created it): its exported snapshot is long gone by now, so the
+    /// snapshot falls back to a plain `REPEATABLE READ` read in that case.
+    async fn ensure_slot_with_snapshot(
+        client: &tokio_postgres::Client,
+        config: &ReplicationConfig,
+    ) -> PyResult<Option<String>> {
+        let existing = client
+            .query_opt(
+                "SELECT 1 FROM pg_replication_slots WHERE slot_name = $1",
+                &[&config.slot_name],
+            )
+            .await
+            .map_err(to_py_err)?;
+        if existing.is_some() {
+            return Ok(None);
+        }
+
+        let mut repl_conn_str = build_connection_string(
+            &config.host,
+            &config.database,
+            config.port,
+            &config.user,
+            &config.password,
+            config.hostaddr.as_deref(),
+        );
+        repl_conn_str.push_str(" replication='database'");
+
+        let (repl_client, repl_connection) =
+            tokio_postgres::connect(&repl_conn_str, NoTls)
+                .await
+                .map_err(|e| to_py_err(format!("Replication connection failed: {}", e)))?;
+        tokio::spawn(async move {
+            if let Err(e) = repl_connection.await {
+                eprintln!("Replication connection error: {}", e);
+            }
+        });
+
+        let messages = repl_client
+            .simple_query(&format!(
+                "CREATE_REPLICATION_SLOT \"{}\" LOGICAL pgoutput EXPORT_SNAPSHOT",
+                config.slot_name.replace('"', "\"\"")
+            ))
+            .await
+            .map_err(to_py_err)?;
+
+        Ok(messages.into_iter().find_map(|m| match m {
+            tokio_postgres::SimpleQueryMessage::Row(row) => {
+                row.get("snapshot_name").map(|s| s.to_string())
+            }
             _ => None,
+        }))
+    }
+
+    /// Read a consistent snapshot of every table in `config.publication_name`
+    /// over a dedicated `tokio_postgres` connection, emitting one `op="r"`
+    /// `ReplicationMessage` per row into `state.snapshot_queue`.
+    ///
+    /// Runs inside a single `REPEATABLE READ` transaction so all tables are
+    /// read as of the same point in time. When the replication slot doesn't
+    /// exist yet, creates it first via `ensure_slot_with_snapshot` and reads
+    /// using its exported snapshot, so this snapshot and the slot's starting
+    /// LSN are exactly in sync and streaming transitions with no gap.
+    async fn run_snapshot(
+        state: &Arc<Mutex<ReaderState>>,
+        config: &ReplicationConfig,
+    ) -> PyResult<()> {
+        let conn_str = build_connection_string(
+            &config.host,
+            &config.database,
+            config.port,
+            &config.user,
+            &config.password,
+            config.hostaddr.as_deref(),
+        );
+        let (client, connection) = tokio_postgres::connect(&conn_str, NoTls)
+            .await
+            .map_err(|e| to_py_err(format!("Snapshot connection failed: {}", e)))?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                eprintln!("Snapshot connection error: {}", e);
+            }
+        });
+
+        let snapshot_name = Self::ensure_slot_with_snapshot(&client, config).await?;
+
+        client
+            .batch_execute("BEGIN ISOLATION LEVEL REPEATABLE READ READ ONLY")
+            .await
+            .map_err(to_py_err)?;
+
+        if let Some(snapshot_name) = &snapshot_name {
+            client
+                .batch_execute(&format!("SET TRANSACTION SNAPSHOT '{}'", snapshot_name))
+                .await
+                .map_err(to_py_err)?;
+        }
+
+        let tables = client
+            .query(
+                "SELECT schemaname, tablename FROM pg_publication_tables WHERE pubname = $1",
+                &[&config.publication_name],
+            )
+            .await
+            .map_err(to_py_err)?;
+
+        let ts_ms = {
+            use std::time::{SystemTime, UNIX_EPOCH};
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as i64)
+                .unwrap_or(0)
+        };
+
+        for table_row in &tables {
+            let schema: String = table_row.get(0);
+            let table: String = table_row.get(1);
+
+            let query = format!(
+                "SELECT row_to_json(t) FROM (SELECT * FROM \"{}\".\"{}\") t",
+                schema.replace('"', "\"\""),
+                table.replace('"', "\"\"")
+            );
+            let rows = client.query(query.as_str(), &[]).await.map_err(to_py_err)?;
+
+            for row in &rows {
+                let row_json: serde_json::Value = row.get(0);
+                let message = Python::with_gil(|py| -> PyResult<ReplicationMessage> {
+                    let after = crate::pgoutput::json_to_py(py, &row_json)?;
+                    let source = create_source_metadata(
+                        py,
+                        0,
+                        ts_ms,
+                        &config.database,
+                        &schema,
+                        &table,
+                        true,
+                    );
+                    Ok(ReplicationMessage {
+                        before: None,
+                        after: Some(after),
+                        source,
+                        op: "r".to_string(),
+                        ts_ms,
+                        ts_us: None,
+                        ts_ns: None,
+                    })
+                })?;
+
+                config
+                    .metrics
+                    .record_message("r", row_json.to_string().len());
+                let mut state_guard = state.lock().await;
+                state_guard.snapshot_queue.push_back(message);
+            }
         }
+
+        client.batch_execute("COMMIT").await.map_err(to_py_err)?;
+        Ok(())
     }
 }
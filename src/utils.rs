@@ -1,27 +1,91 @@
+#[cfg(feature = "python")]
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
 use std::time::Duration;
 use tokio::time::sleep;
-use pyo3::prelude::*;
-use pyo3::exceptions::PyRuntimeError;
 
-/// Build a PostgreSQL connection string from parameters
+use pgwire_replication::TlsConfig;
+
+/// Build a PostgreSQL connection string from parameters.
+///
+/// `hostaddr`, if given, is a numeric IPv4/IPv6 address emitted alongside
+/// `host`, matching libpq semantics: `hostaddr` supplies the address
+/// directly (skipping DNS resolution), while `host` is still sent for TLS
+/// hostname verification. Only used for the plain `tokio_postgres`
+/// connections this builds a connection string for (snapshot/slot-creation);
+/// `connect_with_backoff`'s replication-protocol reconnect loop has no
+/// equivalent field to accept a pre-resolved address, so it still resolves
+/// `host` via DNS on every attempt.
 pub fn build_connection_string(
     host: &str,
     database: &str,
     port: u16,
     user: &str,
     password: &str,
+    hostaddr: Option<&str>,
 ) -> String {
     // Use key-value format which is more reliable
     // Quote values that might contain special characters
     // Note: replication mode is established via SQL commands, not connection params
-    format!(
+    let mut conn_str = format!(
         "host='{}' port='{}' dbname='{}' user='{}' password='{}'",
         host.replace("'", "\\'"),
         port,
         database.replace("'", "\\'"),
         user.replace("'", "\\'"),
         password.replace("'", "\\'")
-    )
+    );
+
+    if let Some(hostaddr) = hostaddr {
+        conn_str.push_str(&format!(" hostaddr='{}'", hostaddr.replace("'", "\\'")));
+    }
+
+    conn_str
+}
+
+/// Build a `TlsConfig` from libpq-style `sslmode` and certificate paths.
+///
+/// `sslmode` follows the libpq subset relevant to replication connections:
+/// `"disable"` (no TLS), `"require"` (encrypt, skip certificate verification),
+/// `"verify-ca"` and `"verify-full"` (verify the server certificate against
+/// `ssl_root_cert`, with `"verify-full"` additionally checking the hostname).
+/// `allow_invalid_certs` downgrades `verify-ca`/`verify-full` to accept an
+/// otherwise-invalid certificate chain; only meant for development.
+#[cfg(feature = "python")]
+pub fn build_tls_config(
+    sslmode: &str,
+    ssl_root_cert: Option<&str>,
+    ssl_cert: Option<&str>,
+    ssl_key: Option<&str>,
+    allow_invalid_certs: bool,
+) -> PyResult<TlsConfig> {
+    let mut tls = match sslmode {
+        "disable" => TlsConfig::disabled(),
+        "require" => TlsConfig::require(),
+        "verify-ca" => TlsConfig::verify_ca(ssl_root_cert.ok_or_else(|| {
+            PyValueError::new_err("sslmode='verify-ca' requires ssl_root_cert")
+        })?),
+        "verify-full" => TlsConfig::verify_full(ssl_root_cert.ok_or_else(|| {
+            PyValueError::new_err("sslmode='verify-full' requires ssl_root_cert")
+        })?),
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "unknown sslmode '{}' (expected \"disable\", \"require\", \"verify-ca\" or \"verify-full\")",
+                other
+            )))
+        }
+    };
+
+    if let (Some(cert), Some(key)) = (ssl_cert, ssl_key) {
+        tls = tls.with_client_cert(cert, key);
+    }
+
+    if allow_invalid_certs {
+        tls = tls.allow_invalid_certs(true);
+    }
+
+    Ok(tls)
 }
 
 /// Exponential backoff implementation for reconnection attempts
@@ -41,15 +105,15 @@ impl ExponentialBackoff {
             attempts: 0,
         }
     }
-    
+
     pub async fn wait(&mut self) {
         sleep(self.current_delay).await;
         self.attempts += 1;
-        
+
         let next_delay = self.current_delay.as_millis() as f64 * self.multiplier;
         self.current_delay = Duration::from_millis(next_delay as u64).min(self.max_delay);
     }
-    
+
     pub fn reset(&mut self) {
         self.current_delay = Duration::from_millis(100);
         self.attempts = 0;
@@ -57,6 +121,7 @@ impl ExponentialBackoff {
 }
 
 /// Convert Rust errors to Python exceptions
+#[cfg(feature = "python")]
 pub fn to_py_err<E: std::fmt::Display>(error: E) -> PyErr {
     PyRuntimeError::new_err(format!("{}", error))
 }